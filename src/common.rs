@@ -0,0 +1,45 @@
+//! Infrastructure shared by all puzzle years.
+
+pub mod grid;
+pub mod groups;
+pub mod helpers;
+pub mod json;
+pub mod registry;
+#[cfg(test)]
+pub mod testing;
+
+/// Get the input for a given year and day, as a &str.
+///
+/// Inputs live under `inputs/<year>/<day>`.
+#[cfg(test)]
+macro_rules! input {
+    ($year:expr, $n:expr) => {
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/inputs/",
+            $year,
+            "/",
+            $n
+        ))
+    };
+}
+#[cfg(test)]
+pub(crate) use input;
+
+/// Get an example for a given year and day, as a &str.
+///
+/// Examples live under `examples/<year>/<day>`.
+#[cfg(test)]
+macro_rules! example {
+    ($year:expr, $n:expr) => {
+        include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/examples/",
+            $year,
+            "/",
+            $n
+        ))
+    };
+}
+#[cfg(test)]
+pub(crate) use example;
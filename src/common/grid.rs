@@ -0,0 +1,31 @@
+use anyhow::{ensure, Result};
+
+/// Parse a non-empty, rectangular grid, with one row per line, and one cell
+/// per char.
+pub fn parse_grid<T>(input: &str, mut cell: impl FnMut(char) -> Result<T>) -> Result<Vec<Vec<T>>> {
+    let grid: Vec<Vec<T>> = input
+        .lines()
+        .map(|line| line.chars().map(&mut cell).collect())
+        .collect::<Result<_>>()?;
+
+    check_rectangular(&grid)?;
+    Ok(grid)
+}
+
+/// Fail unless `grid` is non-empty and rectangular.
+pub fn check_rectangular<T>(grid: &[Vec<T>]) -> Result<()> {
+    ensure!(!grid.is_empty(), "empty grid not allowed");
+
+    let width = grid[0].len();
+    ensure!(width != 0, "empty rows not allowed");
+
+    for (i, row) in grid.iter().enumerate() {
+        let len = row.len();
+        ensure!(
+            len == width,
+            "row 0 and row {i} have different widths: {width} vs {len}"
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,180 @@
+use std::{
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    error::Error,
+    mem,
+    str::FromStr,
+};
+
+use anyhow::{Context, Result};
+
+/// A run of numbers, one per line, delimited by blank lines.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group<T> {
+    /// 0-indexed position among all groups in the input.
+    pub index: usize,
+    pub items: Vec<T>,
+    pub total: T,
+}
+
+/// Integers that can be added up without overflowing silently.
+pub trait CheckedAdd: Sized {
+    fn checked_add(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_checked_add {
+    ($($t:ty),*) => {
+        $(
+            impl CheckedAdd for $t {
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_checked_add!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// Parse blank-line-separated groups of numbers. Errors give 1-indexed group
+/// and line numbers, or just the group number if its total overflows.
+///
+/// Runs of several blank lines count as a single separator, so there are no
+/// empty groups.
+pub fn parse_groups<T>(input: &str) -> Result<Vec<Group<T>>>
+where
+    T: FromStr + CheckedAdd + Default + Copy,
+    T::Err: Error + Send + Sync + 'static,
+{
+    let mut groups = vec![];
+    let mut items = vec![];
+
+    for (line, line_num) in input.lines().zip(1..) {
+        let line = line.trim();
+        if line.is_empty() {
+            push_group(&mut groups, &mut items)?;
+            continue;
+        }
+
+        let x = line.parse().with_context(|| {
+            let group_num = groups.len() + 1;
+            format!("group {group_num}, line {line_num}: invalid number {line:?}")
+        })?;
+        items.push(x);
+    }
+    push_group(&mut groups, &mut items)?;
+
+    Ok(groups)
+}
+
+/// Helper for `parse_groups`. Does nothing if `items` is empty.
+fn push_group<T>(groups: &mut Vec<Group<T>>, items: &mut Vec<T>) -> Result<()>
+where
+    T: CheckedAdd + Default + Copy,
+{
+    if items.is_empty() {
+        return Ok(());
+    }
+
+    let items = mem::take(items);
+    let total = items
+        .iter()
+        .try_fold(T::default(), |total, &x| total.checked_add(x))
+        .with_context(|| format!("group {}: total overflows", groups.len() + 1))?;
+    groups.push(Group {
+        index: groups.len(),
+        items,
+        total,
+    });
+    Ok(())
+}
+
+/// Return the `k` items with the largest keys, largest first.
+///
+/// Ties are broken in favour of earlier items. Runs in O(n log k) time and
+/// O(k) space, by keeping the best `k` so far in a bounded min-heap.
+pub fn top_k<T, K: Ord>(
+    items: impl IntoIterator<Item = T>,
+    k: usize,
+    mut key: impl FnMut(&T) -> K,
+) -> Vec<T> {
+    if k == 0 {
+        return vec![];
+    }
+
+    let mut heap = BinaryHeap::with_capacity(k + 1);
+    for (seq, item) in items.into_iter().enumerate() {
+        heap.push(Reverse(Ranked {
+            key: key(&item),
+            seq: Reverse(seq),
+            item,
+        }));
+
+        // Evict the smallest.
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+
+    // Ascending order of `Reverse`, so descending order of rank.
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|Reverse(r)| r.item)
+        .collect()
+}
+
+/// Helper for `top_k`. Ordered by key, then by *earliest* position.
+struct Ranked<T, K> {
+    key: K,
+    seq: Reverse<usize>,
+    item: T,
+}
+
+impl<T, K: Ord> Ord for Ranked<T, K> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (&self.key, self.seq).cmp(&(&other.key, other.seq))
+    }
+}
+
+impl<T, K: Ord> PartialOrd for Ranked<T, K> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, K: Ord> PartialEq for Ranked<T, K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<T, K: Ord> Eq for Ranked<T, K> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_k_matches_sorting() {
+        let xs = [5, 1, 9, 3, 9, 7, 2, 8];
+        assert_eq!(top_k(xs, 3, |&x| x), vec![9, 9, 8]);
+        assert_eq!(top_k(xs, 0, |&x| x), Vec::<i32>::new());
+        assert_eq!(top_k(xs, 100, |&x| x), vec![9, 9, 8, 7, 5, 3, 2, 1]);
+    }
+
+    #[test]
+    fn top_k_ties_prefer_earlier() {
+        let xs = [(1, 'a'), (2, 'b'), (2, 'c'), (1, 'd')];
+        assert_eq!(top_k(xs, 2, |&(x, _)| x), vec![(2, 'b'), (2, 'c')]);
+        assert_eq!(top_k(xs, 3, |&(x, _)| x)[2], (1, 'a'));
+    }
+
+    #[test]
+    fn parse_errors_have_locations() {
+        let err = parse_groups::<u32>("1\n2\n\n3\nx\n").unwrap_err();
+        assert_eq!(err.to_string(), "group 2, line 5: invalid number \"x\"");
+
+        let err = parse_groups::<u32>("1\n\n4294967295\n1\n").unwrap_err();
+        assert_eq!(err.to_string(), "group 2: total overflows");
+    }
+}
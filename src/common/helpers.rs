@@ -8,3 +8,13 @@ pub fn until_err<T, E>(err: &mut &mut Result<(), E>, item: Result<T, E>) -> Opti
         }
     }
 }
+
+/// Return None if the result would be negative.
+pub fn checked_add(x: usize, y: isize) -> Option<usize> {
+    let z = (x as isize) + y;
+    if z >= 0 {
+        Some(z as usize)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,472 @@
+//! A small JSON reader and writer, since we don't depend on serde.
+
+use std::fmt::{self, Display, Write};
+
+use anyhow::{bail, ensure, Context, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// Kept as written, so integers of any size survive a round trip.
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// Keys are kept in order.
+    Object(Vec<(String, Json)>),
+}
+
+impl From<usize> for Json {
+    fn from(n: usize) -> Self {
+        Self::Number(n.to_string())
+    }
+}
+
+impl From<bool> for Json {
+    fn from(b: bool) -> Self {
+        Self::Bool(b)
+    }
+}
+
+impl From<&str> for Json {
+    fn from(s: &str) -> Self {
+        Self::String(s.to_owned())
+    }
+}
+
+impl Json {
+    /// Fails on anything nested more than `MAX_DEPTH` arrays or objects deep,
+    /// rather than overflowing the stack.
+    pub fn parse(s: &str) -> Result<Self> {
+        let mut p = Parser {
+            s,
+            pos: 0,
+            depth: 0,
+        };
+        let value = p.value()?;
+        p.skip_whitespace();
+        ensure!(p.pos == s.len(), "byte {}: trailing characters", p.pos);
+        Ok(value)
+    }
+
+    /// Multi-line, indented by two spaces per level.
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        self.write_pretty(&mut out, 0);
+        out.push('\n');
+        out
+    }
+
+    fn write_pretty(&self, out: &mut String, depth: usize) {
+        let indent = |out: &mut String, depth| out.push_str(&"  ".repeat(depth));
+
+        match self {
+            Self::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    indent(out, depth + 1);
+                    item.write_pretty(out, depth + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push(']');
+            }
+            Self::Object(fields) if !fields.is_empty() => {
+                out.push_str("{\n");
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    indent(out, depth + 1);
+                    write_string(out, key).unwrap();
+                    out.push_str(": ");
+                    value.write_pretty(out, depth + 1);
+                    out.push_str(if i + 1 < fields.len() { ",\n" } else { "\n" });
+                }
+                indent(out, depth);
+                out.push('}');
+            }
+            _ => write!(out, "{self}").unwrap(),
+        }
+    }
+
+    pub fn as_str(&self) -> Result<&str> {
+        match self {
+            Self::String(s) => Ok(s),
+            _ => bail!("expected a string, got {self}"),
+        }
+    }
+
+    pub fn as_bool(&self) -> Result<bool> {
+        match self {
+            Self::Bool(b) => Ok(*b),
+            _ => bail!("expected a bool, got {self}"),
+        }
+    }
+
+    pub fn as_usize(&self) -> Result<usize> {
+        match self {
+            Self::Number(n) => n
+                .parse()
+                .with_context(|| format!("expected a non-negative integer, got {n}")),
+            _ => bail!("expected a number, got {self}"),
+        }
+    }
+
+    pub fn as_array(&self) -> Result<&[Json]> {
+        match self {
+            Self::Array(items) => Ok(items),
+            _ => bail!("expected an array, got {self}"),
+        }
+    }
+
+    pub fn as_object(&self) -> Result<&[(String, Json)]> {
+        match self {
+            Self::Object(fields) => Ok(fields),
+            _ => bail!("expected an object, got {self}"),
+        }
+    }
+}
+
+/// Compact, on one line.
+impl Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Null => write!(f, "null"),
+            Self::Bool(b) => write!(f, "{b}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::String(s) => write_string(f, s),
+            Self::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{item}")?;
+                }
+                write!(f, "]")
+            }
+            Self::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i != 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{value}")?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+fn write_string(out: &mut impl Write, s: &str) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+/// Recursive descent, over a `&str`.
+/// See `Json::parse`.
+pub const MAX_DEPTH: usize = 512;
+
+struct Parser<'a> {
+    s: &'a str,
+    /// Byte offset.
+    pos: usize,
+    /// How many arrays and objects we're inside.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<()> {
+        ensure!(
+            self.peek() == Some(b),
+            "byte {}: expected {:?}",
+            self.pos,
+            b as char
+        );
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn value(&mut self) -> Result<Json> {
+        self.skip_whitespace();
+        let value = match self.peek() {
+            Some(b'n') => self.keyword("null", Json::Null)?,
+            Some(b't') => self.keyword("true", Json::Bool(true))?,
+            Some(b'f') => self.keyword("false", Json::Bool(false))?,
+            Some(b'"') => Json::String(self.string()?),
+            Some(b'[') => self.nested(Self::array)?,
+            Some(b'{') => self.nested(Self::object)?,
+            Some(b'-' | b'0'..=b'9') => self.number()?,
+            Some(_) => bail!("byte {}: unexpected character", self.pos),
+            None => bail!("byte {}: unexpected end of input", self.pos),
+        };
+        Ok(value)
+    }
+
+    /// Parse an array or object, one level deeper.
+    fn nested(&mut self, parse: fn(&mut Self) -> Result<Json>) -> Result<Json> {
+        self.depth += 1;
+        ensure!(
+            self.depth <= MAX_DEPTH,
+            "byte {}: nested too deeply",
+            self.pos
+        );
+        let value = parse(self)?;
+        self.depth -= 1;
+        Ok(value)
+    }
+
+    fn keyword(&mut self, word: &str, value: Json) -> Result<Json> {
+        ensure!(
+            self.s[self.pos..].starts_with(word),
+            "byte {}: expected {word}",
+            self.pos
+        );
+        self.pos += word.len();
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Json> {
+        let start = self.pos;
+        let digits = |p: &mut Self| {
+            let from = p.pos;
+            while matches!(p.peek(), Some(b'0'..=b'9')) {
+                p.pos += 1;
+            }
+            ensure!(p.pos > from, "byte {}: expected a digit", p.pos);
+            Ok(())
+        };
+
+        if self.peek() == Some(b'-') {
+            self.pos += 1;
+        }
+        if self.peek() == Some(b'0') {
+            self.pos += 1;
+        } else {
+            digits(self)?;
+        }
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            digits(self)?;
+        }
+        if let Some(b'e' | b'E') = self.peek() {
+            self.pos += 1;
+            if let Some(b'+' | b'-') = self.peek() {
+                self.pos += 1;
+            }
+            digits(self)?;
+        }
+
+        Ok(Json::Number(self.s[start..self.pos].to_owned()))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+
+        loop {
+            let rest = &self.s[self.pos..];
+            let c = rest
+                .chars()
+                .next()
+                .with_context(|| format!("byte {}: unterminated string", self.pos))?;
+            self.pos += c.len_utf8();
+
+            match c {
+                '"' => return Ok(out),
+                '\\' => out.push(self.escape()?),
+                c if (c as u32) < 0x20 => {
+                    bail!("byte {}: control character in string", self.pos - 1)
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// Helper for `string`, just after a backslash.
+    fn escape(&mut self) -> Result<char> {
+        let b = self
+            .peek()
+            .with_context(|| format!("byte {}: unterminated escape", self.pos))?;
+        self.pos += 1;
+
+        let c = match b {
+            b'"' => '"',
+            b'\\' => '\\',
+            b'/' => '/',
+            b'b' => '\u{8}',
+            b'f' => '\u{c}',
+            b'n' => '\n',
+            b'r' => '\r',
+            b't' => '\t',
+            b'u' => {
+                let hi = self.hex4()?;
+                if (0xD800..0xDC00).contains(&hi) {
+                    // A surrogate pair.
+                    self.expect(b'\\')?;
+                    self.expect(b'u')?;
+                    let lo = self.hex4()?;
+                    ensure!(
+                        (0xDC00..0xE000).contains(&lo),
+                        "byte {}: invalid surrogate pair",
+                        self.pos
+                    );
+                    let code = 0x10000 + ((hi - 0xD800) << 10) + (lo - 0xDC00);
+                    char::from_u32(code).context("invalid code point")?
+                } else {
+                    char::from_u32(hi)
+                        .with_context(|| format!("byte {}: invalid code point", self.pos))?
+                }
+            }
+            _ => bail!("byte {}: invalid escape", self.pos - 1),
+        };
+        Ok(c)
+    }
+
+    fn hex4(&mut self) -> Result<u32> {
+        let hex = self
+            .s
+            .get(self.pos..self.pos + 4)
+            .with_context(|| format!("byte {}: truncated \\u escape", self.pos))?;
+        // `from_str_radix` would also take a sign.
+        ensure!(
+            hex.bytes().all(|b| b.is_ascii_hexdigit()),
+            "byte {}: invalid \\u escape",
+            self.pos
+        );
+        let code = u32::from_str_radix(hex, 16).unwrap();
+        self.pos += 4;
+        Ok(code)
+    }
+
+    fn array(&mut self) -> Result<Json> {
+        self.expect(b'[')?;
+        let mut items = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Ok(Json::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b']') => {
+                    self.pos += 1;
+                    return Ok(Json::Array(items));
+                }
+                _ => bail!("byte {}: expected ',' or ']'", self.pos),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Json> {
+        self.expect(b'{')?;
+        let mut fields = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.skip_whitespace();
+            self.expect(b':')?;
+            let value = self.value()?;
+            fields.push((key, value));
+
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => bail!("byte {}: expected ',' or '}}'", self.pos),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Json, MAX_DEPTH};
+
+    #[test]
+    fn round_trip() -> Result<()> {
+        let text =
+            r#"{"a": [1, -2.5e3, true, null], "b\n\"c": "\u00e9\ud83d\ude00\t", "d": {}, "e": []}"#;
+        let value = Json::parse(text)?;
+
+        let Json::Object(fields) = &value else {
+            panic!("expected an object");
+        };
+        assert_eq!(fields[1].0, "b\n\"c");
+        assert_eq!(fields[1].1, Json::from("é😀\t"));
+
+        assert_eq!(Json::parse(&value.to_string())?, value);
+        assert_eq!(Json::parse(&value.pretty())?, value);
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        for bad in [
+            "",
+            "[1,]",
+            "{\"a\" 1}",
+            "01",
+            "\"abc",
+            "[1] 2",
+            "tru",
+            "\"\\x\"",
+            "\"\\u+123\"",
+            "\"\\u-123\"",
+        ] {
+            assert!(Json::parse(bad).is_err(), "{bad:?}");
+        }
+    }
+
+    #[test]
+    fn nesting() {
+        let nested = |n| "[".repeat(n) + &"]".repeat(n);
+        assert!(Json::parse(&nested(MAX_DEPTH)).is_ok());
+
+        let err = Json::parse(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("byte {MAX_DEPTH}: nested too deeply")
+        );
+        assert!(Json::parse(&"[".repeat(200_000)).is_err());
+        assert!(Json::parse(&"{\"a\":".repeat(200_000)).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+use anyhow::Result;
+
+/// Solve one part of a puzzle, given its input.
+///
+/// The answer is formatted for display, since its type varies by puzzle.
+pub type Solver = fn(&str) -> Result<String>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+    /// None if that part hasn't been solved yet.
+    pub part_1: Option<Solver>,
+    pub part_2: Option<Solver>,
+}
@@ -1,34 +1,41 @@
-#![cfg(test)]
-
-mod helpers;
-
-mod day_1;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-
-/// Get the input for a given day, as a &str.
-macro_rules! input {
-    ($n:expr) => {
-        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/inputs/", $n))
-    };
+//! Advent of Code solutions.
+//!
+//! Each puzzle year is its own module (e.g. `y2022::day_12`), and code that's
+//! shared between years lives in `common`. Every day exposes its parser,
+//! domain types, and part solvers. The tests that run the solvers against the
+//! real puzzle inputs live in a `tests` submodule of each day, so the inputs
+//! are only compiled into test builds.
+
+pub mod common;
+
+pub mod y2022;
+
+use common::registry::Puzzle;
+
+/// Every registered puzzle, ordered by (year, day).
+pub fn puzzles() -> impl Iterator<Item = &'static Puzzle> {
+    [y2022::PUZZLES].into_iter().flatten()
 }
-use input;
 
-#[allow(unused_macros)]
-macro_rules! example {
-    ($n:expr) => {
-        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/examples/", $n))
-    };
+/// Look up a puzzle by (year, day).
+pub fn puzzle(year: u16, day: u8) -> Option<&'static Puzzle> {
+    puzzles().find(|p| (p.year, p.day) == (year, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    #[test]
+    fn puzzles_are_sorted_and_unique() {
+        let keys: Vec<_> = super::puzzles().map(|p| (p.year, p.day)).collect();
+        assert!(keys.iter().tuple_windows().all(|(a, b)| a < b));
+    }
+
+    #[test]
+    fn lookup() {
+        let p = super::puzzle(2022, 12).unwrap();
+        assert_eq!((p.year, p.day), (2022, 12));
+        assert!(super::puzzle(2022, 26).is_none());
+    }
 }
-#[allow(unused_imports)]
-use example;
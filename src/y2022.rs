@@ -0,0 +1,124 @@
+//! Advent of Code 2022.
+
+pub mod day_1;
+pub mod day_2;
+pub mod day_3;
+pub mod day_4;
+pub mod day_5;
+pub mod day_6;
+pub mod day_7;
+pub mod day_8;
+pub mod day_9;
+pub mod day_10;
+pub mod day_11;
+pub mod day_12;
+pub mod day_13;
+pub mod day_14;
+pub mod day_15;
+pub mod day_16;
+
+use crate::common::registry::Puzzle;
+
+/// The 2022 puzzles, ordered by day.
+pub static PUZZLES: &[Puzzle] = &[
+    Puzzle {
+        year: 2022,
+        day: 1,
+        part_1: Some(|s| Ok(day_1::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_1::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 2,
+        part_1: Some(|s| Ok(day_2::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_2::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 3,
+        part_1: Some(|s| Ok(day_3::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_3::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 4,
+        part_1: Some(|s| Ok(day_4::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_4::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 5,
+        part_1: Some(day_5::part_1),
+        part_2: Some(day_5::part_2),
+    },
+    Puzzle {
+        year: 2022,
+        day: 6,
+        part_1: Some(|s| Ok(day_6::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_6::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 7,
+        part_1: Some(|s| Ok(day_7::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_7::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 8,
+        part_1: Some(|s| Ok(day_8::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_8::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 9,
+        part_1: Some(|s| Ok(day_9::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_9::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 10,
+        part_1: Some(|s| Ok(day_10::part_1(s)?.to_string())),
+        part_2: Some(day_10::part_2),
+    },
+    Puzzle {
+        year: 2022,
+        day: 11,
+        // The monkeys are hardcoded, so the input is ignored.
+        part_1: Some(|_| Ok(day_11::part_1().to_string())),
+        part_2: Some(|_| Ok(day_11::part_2().to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 12,
+        part_1: Some(|s| Ok(day_12::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_12::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 13,
+        part_1: Some(|s| Ok(day_13::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_13::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 14,
+        part_1: Some(|s| Ok(day_14::part_1(s)?.to_string())),
+        part_2: Some(|s| Ok(day_14::part_2(s)?.to_string())),
+    },
+    Puzzle {
+        year: 2022,
+        day: 15,
+        part_1: Some(|s| Ok(day_15::part_1(s)?.to_string())),
+        part_2: Some(|s| {
+            let p = day_15::part_2(s)?;
+            Ok(format!("{},{}", p.x, p.y))
+        }),
+    },
+    Puzzle {
+        year: 2022,
+        day: 16,
+        part_1: None,
+        part_2: None,
+    },
+];
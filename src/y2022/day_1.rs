@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+
+use crate::common::groups::{parse_groups, top_k, Group};
+
+/// The calories carried by each elf, in input order.
+pub type Elf = Group<u32>;
+
+pub fn parse_input(input: &str) -> Result<Vec<Elf>> {
+    parse_groups(input)
+}
+
+/// The `k` elves carrying the most calories, most first.
+pub fn top_elves(elves: Vec<Elf>, k: usize) -> Vec<Elf> {
+    top_k(elves, k, |elf| elf.total)
+}
+
+/// The most calories carried by any one elf.
+pub fn part_1(input: &str) -> Result<u32> {
+    let elves = parse_input(input)?;
+    let top = top_elves(elves, 1);
+    Ok(top.first().context("no elves")?.total)
+}
+
+/// The calories carried by the top three elves, combined.
+pub fn part_2(input: &str) -> Result<u32> {
+    let elves = parse_input(input)?;
+    top_elves(elves, 3)
+        .iter()
+        .try_fold(0u32, |total, elf| total.checked_add(elf.total))
+        .context("the top three totals overflow")
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{parse_input, top_elves};
+    use crate::common::{example, input};
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let max_sum = super::part_1(input!(2022, 1))?;
+        dbg!(max_sum);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let three_largest = super::part_2(input!(2022, 1))?;
+        dbg!(three_largest);
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        let input = example!(2022, 1);
+        assert_eq!(super::part_1(input)?, 24_000);
+        assert_eq!(super::part_2(input)?, 45_000);
+
+        let top = top_elves(parse_input(input)?, 3);
+        let indices: Vec<_> = top.iter().map(|elf| elf.index).collect();
+        assert_eq!(indices, [3, 2, 4]);
+        assert_eq!(top[0].items, [7_000, 8_000, 9_000]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn overflow() {
+        assert!(super::part_1("4294967295\n1\n").is_err());
+        assert!(super::part_2("4294967295\n\n1\n").is_err());
+    }
+}
@@ -0,0 +1,237 @@
+mod asm;
+mod cpu;
+mod ocr;
+
+use std::ops::Index;
+
+use anyhow::{ensure, Context, Result};
+
+pub use self::{
+    asm::parse_input,
+    cpu::{Breakpoint, Cpu, State, Stop},
+    ocr::read_screen,
+};
+
+/// The sum of the signal strengths during the 20th, 60th, ..., 220th cycles.
+pub fn part_1(input: &str) -> Result<i64> {
+    let instrs = parse_input(input)?;
+    // Programs can loop forever, so only run for as long as we need.
+    let trace: Vec<_> = Cpu::new(&instrs).trace().take(220).collect();
+    signal_strength_sum(&trace)
+}
+
+/// The capital letters drawn on the CRT.
+pub fn part_2(input: &str) -> Result<String> {
+    let instrs = parse_input(input)?;
+    let trace: Vec<_> = Cpu::new(&instrs).trace().take(240).collect();
+    read_screen(&draw_screen(&trace)?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    NoOp,
+    Add {
+        reg: Reg,
+        value: Operand,
+    },
+    Mul {
+        reg: Reg,
+        value: Operand,
+    },
+    Set {
+        reg: Reg,
+        value: Operand,
+    },
+    /// `target` is an index into the program.
+    Jump {
+        cond: Cond,
+        target: usize,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reg {
+    X,
+    Y,
+    Z,
+    W,
+}
+
+impl Reg {
+    pub const COUNT: usize = 4;
+}
+
+/// The registers, indexed by `Reg`. X starts at 1, and the rest at 0.
+pub type Regs = [i32; Reg::COUNT];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Imm(i32),
+    Reg(Reg),
+}
+
+impl Operand {
+    pub fn value(self, regs: &Regs) -> i32 {
+        match self {
+            Self::Imm(value) => value,
+            Self::Reg(reg) => regs[reg as usize],
+        }
+    }
+}
+
+/// When a `Jump` is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cond {
+    Always,
+    Zero(Reg),
+    NonZero(Reg),
+}
+
+impl Cond {
+    pub fn holds(self, regs: &Regs) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Zero(reg) => regs[reg as usize] == 0,
+            Self::NonZero(reg) => regs[reg as usize] != 0,
+        }
+    }
+}
+
+/// Instructions, without their arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    NoOp,
+    Add,
+    Mul,
+    Set,
+    Jump,
+}
+
+impl Instr {
+    pub fn op(self) -> Op {
+        match self {
+            Self::NoOp => Op::NoOp,
+            Self::Add { .. } => Op::Add,
+            Self::Mul { .. } => Op::Mul,
+            Self::Set { .. } => Op::Set,
+            Self::Jump { .. } => Op::Jump,
+        }
+    }
+}
+
+/// How many cycles each kind of instruction takes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Costs {
+    cycles: [u32; 5],
+}
+
+/// `noop` and `addx` take 1 and 2 cycles, like the puzzle says. The rest are
+/// made up: `mul` takes 3, `set` 1 and jumps 2.
+impl Default for Costs {
+    fn default() -> Self {
+        Self {
+            cycles: [1, 2, 3, 1, 2],
+        }
+    }
+}
+
+impl Index<Op> for Costs {
+    type Output = u32;
+
+    fn index(&self, op: Op) -> &u32 {
+        &self.cycles[op as usize]
+    }
+}
+
+impl Costs {
+    /// Fails if `cycles` is 0; every instruction takes at least one cycle.
+    pub fn set(&mut self, op: Op, cycles: u32) -> Result<()> {
+        ensure!(cycles != 0, "{op:?} has to take at least one cycle");
+        self.cycles[op as usize] = cycles;
+        Ok(())
+    }
+}
+
+/// The sum of cycle number times X, during the 20th, 60th, 100th, ... cycles.
+/// Fails if that doesn't fit in an `i64`, which takes a very long trace.
+pub fn signal_strength_sum(trace: &[State]) -> Result<i64> {
+    trace
+        .iter()
+        .filter(|s| s.cycle % 40 == 20)
+        .try_fold(0i64, |sum, s| {
+            let strength = i64::try_from(s.cycle).ok()?.checked_mul(s.x() as i64)?;
+            sum.checked_add(strength)
+        })
+        .context("the signal strengths overflow")
+}
+
+/// The CRT draws one pixel per cycle, left to right and then top to bottom. A
+/// pixel is lit if the 3-pixel-wide sprite, centered on X, covers it.
+pub fn draw_screen(trace: &[State]) -> Result<Vec<String>> {
+    let h = 6;
+    let w = 40;
+    let n = h * w;
+    ensure!(
+        trace.len() >= n,
+        "the program only runs for {} cycles, but the screen has {n} pixels",
+        trace.len()
+    );
+
+    let mut screen = vec![String::with_capacity(w); h];
+    for state in &trace[..n] {
+        let i = state.cycle as usize - 1;
+        let x = (i % w) as i64;
+        let pixel = if (x - state.x() as i64).abs() <= 1 {
+            '#'
+        } else {
+            '.'
+        };
+        screen[i / w].push(pixel);
+    }
+    Ok(screen)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::input;
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let answer = super::part_1(input!(2022, 10))?;
+        dbg!(answer);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let answer = super::part_2(input!(2022, 10))?;
+        assert_eq!(answer, "ZKGRKGRK");
+        Ok(())
+    }
+
+    #[test]
+    fn odd_programs() -> Result<()> {
+        // Registers wrap around, and then X can be as big or small as it
+        // likes, for as long as we look.
+        let noops = "\nnoop".repeat(300);
+        let answer = super::part_1(&format!("setx 2147483647\naddx 1{noops}"))?;
+        assert_eq!(answer, i32::MIN as i64 * (20 + 60 + 100 + 140 + 180 + 220));
+        let answer = super::part_1(&format!("setx 2147483647{noops}"))?;
+        assert_eq!(answer, i32::MAX as i64 * (20 + 60 + 100 + 140 + 180 + 220));
+
+        // With X that far off, only the first cycle (while X is still 1) draws
+        // anything, which leaves a glyph we don't know.
+        for x in [i32::MIN, i32::MAX] {
+            let err = super::part_2(&format!("setx {x}{noops}")).unwrap_err();
+            assert!(err.to_string().starts_with("unknown glyph in cell 0"));
+        }
+
+        // Programs that never end still get an answer, or an error.
+        let forever = "loop: addx 1\njmp loop";
+        super::part_1(forever)?;
+        assert!(super::part_2(forever).is_err());
+        Ok(())
+    }
+}
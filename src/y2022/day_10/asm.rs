@@ -0,0 +1,223 @@
+use std::{collections::HashMap, str::FromStr};
+
+use anyhow::{bail, ensure, Context, Result};
+use lazy_regex::regex;
+
+use super::{Cond, Instr, Operand, Reg};
+
+/// Assemble a program, one instruction per line:
+///
+/// * `noop`
+/// * `addx V`, `mulx V`, `setx V`: X += V, X *= V, X = V. V is a number or a
+///   register.
+/// * `jmp L`, `jzx L`, `jnzx L`: jump to label L always, if X is zero, or if
+///   X isn't zero.
+///
+/// Any register (`x`, `y`, `z` or `w`) can stand in for the `x` suffix.
+/// Labels are written `L:`, on their own line or before an instruction, and
+/// `#` starts a comment.
+pub fn parse_input(input: &str) -> Result<Vec<Instr>> {
+    // The first pass finds the labels, and the second resolves them.
+    let mut labels = HashMap::new();
+    let mut lines = vec![];
+
+    for (line, line_num) in input.lines().zip(1..) {
+        let mut line = line.split('#').next().unwrap().trim();
+
+        if let Some(caps) = regex!(r"^([A-Za-z_]\w*):\s*(.*)$").captures(line) {
+            let label = caps.get(1).unwrap().as_str();
+            let prev = labels.insert(label, lines.len());
+            ensure!(prev.is_none(), "line {line_num}: duplicate label {label:?}");
+            line = caps.get(2).unwrap().as_str();
+        }
+
+        if !line.is_empty() {
+            lines.push((line_num, line));
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(line_num, line)| {
+            parse_instr(line, &labels).with_context(|| format!("line {line_num}: {line:?}"))
+        })
+        .collect()
+}
+
+/// A single instruction, without labels to jump to.
+impl FromStr for Instr {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        parse_instr(s.trim(), &HashMap::new())
+    }
+}
+
+/// Helper for `parse_input` and `from_str`.
+fn parse_instr(s: &str, labels: &HashMap<&str, usize>) -> Result<Instr> {
+    let mut words = s.split_whitespace();
+    let op = words.next().context("empty instruction")?;
+    let args: Vec<_> = words.collect();
+
+    let arg = || -> Result<&str> {
+        match args[..] {
+            [arg] => Ok(arg),
+            _ => bail!("{op} takes one argument, got {}", args.len()),
+        }
+    };
+    let label = || -> Result<usize> {
+        let label = arg()?;
+        labels
+            .get(label)
+            .copied()
+            .with_context(|| format!("unknown label {label:?}"))
+    };
+
+    if op == "noop" {
+        ensure!(args.is_empty(), "noop takes no arguments");
+        return Ok(Instr::NoOp);
+    }
+    if op == "jmp" {
+        let target = label()?;
+        return Ok(Instr::Jump {
+            cond: Cond::Always,
+            target,
+        });
+    }
+
+    // The rest all end in a register name.
+    let (i, _) = op.char_indices().last().unwrap();
+    let (op, reg) = op.split_at(i);
+    let reg = reg.parse();
+
+    let instr = match op {
+        "add" => Instr::Add {
+            reg: reg?,
+            value: arg()?.parse()?,
+        },
+        "mul" => Instr::Mul {
+            reg: reg?,
+            value: arg()?.parse()?,
+        },
+        "set" => Instr::Set {
+            reg: reg?,
+            value: arg()?.parse()?,
+        },
+        "jz" => Instr::Jump {
+            cond: Cond::Zero(reg?),
+            target: label()?,
+        },
+        "jnz" => Instr::Jump {
+            cond: Cond::NonZero(reg?),
+            target: label()?,
+        },
+        _ => bail!("unknown instruction"),
+    };
+    Ok(instr)
+}
+
+impl FromStr for Reg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "x" => Ok(Self::X),
+            "y" => Ok(Self::Y),
+            "z" => Ok(Self::Z),
+            "w" => Ok(Self::W),
+            _ => bail!("no register {s:?}"),
+        }
+    }
+}
+
+impl FromStr for Operand {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.parse() {
+            Ok(reg) => Ok(Self::Reg(reg)),
+            Err(_) => {
+                let imm = s
+                    .parse()
+                    .with_context(|| format!("expected a number or a register, not {s:?}"))?;
+                Ok(Self::Imm(imm))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::parse_input;
+    use crate::y2022::day_10::{Cond, Instr, Operand, Reg};
+
+    #[test]
+    fn assemble() -> Result<()> {
+        let program = parse_input(
+            "
+            # Count down from 3.
+            setw 3
+            loop: addx w
+            addw -1
+            jnzw loop
+            done:
+            noop
+            ",
+        )?;
+
+        assert_eq!(
+            program,
+            [
+                Instr::Set {
+                    reg: Reg::W,
+                    value: Operand::Imm(3),
+                },
+                Instr::Add {
+                    reg: Reg::X,
+                    value: Operand::Reg(Reg::W),
+                },
+                Instr::Add {
+                    reg: Reg::W,
+                    value: Operand::Imm(-1),
+                },
+                Instr::Jump {
+                    cond: Cond::NonZero(Reg::W),
+                    target: 1,
+                },
+                Instr::NoOp,
+            ]
+        );
+
+        assert_eq!(
+            "addx -5".parse::<Instr>()?,
+            Instr::Add {
+                reg: Reg::X,
+                value: Operand::Imm(-5),
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn errors() {
+        let cases = [
+            ("noop\nnoop 1", "line 2"),
+            ("noop\n\naddq 1", "line 3"),
+            ("addx", "line 1"),
+            ("addx 1 2", "line 1"),
+            ("addx one", "line 1"),
+            ("jmp nowhere", "line 1"),
+            ("a:\nnoop\na: noop", "line 3"),
+            ("frob", "line 1"),
+            ("addé 1", "line 1"),
+        ];
+
+        for (source, line) in cases {
+            let err = parse_input(source).unwrap_err();
+            let msg = format!("{err:#}");
+            assert!(msg.starts_with(line), "{source:?}: {msg}");
+        }
+    }
+}
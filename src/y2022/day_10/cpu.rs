@@ -0,0 +1,372 @@
+use std::{cmp::Ordering, iter};
+
+use super::{Costs, Instr, Reg, Regs};
+
+/// What the CPU is doing during one cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State {
+    /// 1-indexed, like the puzzle.
+    pub cycle: u64,
+    /// The index of `instr` in the program.
+    pub pc: usize,
+    /// The registers during the cycle, before `instr` (if it finishes this
+    /// cycle) has any effect.
+    pub regs: Regs,
+    /// The instruction in flight.
+    pub instr: Instr,
+    /// How many cycles `instr` has been running, counting this one.
+    pub elapsed: u32,
+}
+
+impl State {
+    pub fn reg(&self, reg: Reg) -> i32 {
+        self.regs[reg as usize]
+    }
+
+    pub fn x(&self) -> i32 {
+        self.reg(Reg::X)
+    }
+}
+
+/// Where `Cpu::run` should stop.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Breakpoint {
+    /// Just before the given cycle.
+    Cycle(u64),
+    /// Just before the instruction at this index starts.
+    Pc(usize),
+    /// Just before a cycle where the register compares to the value like
+    /// this, if it didn't during the cycle before. E.g. `Reg(Reg::X,
+    /// Ordering::Greater, 10)` stops when X becomes more than 10.
+    Reg(Reg, Ordering, i32),
+}
+
+/// Why `Cpu::run` stopped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stop {
+    /// We ran off the end of the program.
+    Halted,
+    /// We hit the breakpoint with this index. The state is that of the cycle
+    /// that's about to run.
+    Breakpoint(usize, State),
+}
+
+/// A cycle-accurate emulator for the CRT's CPU.
+///
+/// Step through it one cycle at a time, collect the whole `trace`, or set some
+/// breakpoints and `run` it.
+///
+/// Each instruction takes effect at the end of its last cycle. That goes for
+/// jumps too, which also check their condition then. Arithmetic wraps around,
+/// like a real 32-bit register.
+#[derive(Debug, Clone)]
+pub struct Cpu<'a> {
+    program: &'a [Instr],
+    costs: Costs,
+    pc: usize,
+    regs: Regs,
+    /// The number of the next cycle to run.
+    cycle: u64,
+    /// Cycles spent on the instruction at `pc` so far.
+    elapsed: u32,
+    breakpoints: Vec<Breakpoint>,
+    /// The registers during the last cycle, for edge-triggering breakpoints.
+    prev_regs: Option<Regs>,
+    /// Set when `run` stops, so that calling it again doesn't stop in the same
+    /// place.
+    paused: bool,
+}
+
+impl<'a> Cpu<'a> {
+    /// With the default `Costs`.
+    pub fn new(program: &'a [Instr]) -> Self {
+        Self::with_costs(program, Costs::default())
+    }
+
+    pub fn with_costs(program: &'a [Instr], costs: Costs) -> Self {
+        Self {
+            program,
+            costs,
+            pc: 0,
+            regs: [1, 0, 0, 0],
+            cycle: 1,
+            elapsed: 0,
+            breakpoints: vec![],
+            prev_regs: None,
+            paused: false,
+        }
+    }
+
+    /// The number of the next cycle to run.
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    pub fn reg(&self, reg: Reg) -> i32 {
+        self.regs[reg as usize]
+    }
+
+    pub fn x(&self) -> i32 {
+        self.reg(Reg::X)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.pc >= self.program.len()
+    }
+
+    /// The state during the next cycle, without running it.
+    pub fn peek(&self) -> Option<State> {
+        let &instr = self.program.get(self.pc)?;
+        Some(State {
+            cycle: self.cycle,
+            pc: self.pc,
+            regs: self.regs,
+            instr,
+            elapsed: self.elapsed + 1,
+        })
+    }
+
+    /// Run one cycle, and return the state during it.
+    pub fn step(&mut self) -> Option<State> {
+        let state = self.peek()?;
+
+        self.paused = false;
+        self.prev_regs = Some(self.regs);
+        self.cycle += 1;
+        self.elapsed += 1;
+
+        if self.elapsed == self.costs[state.instr.op()] {
+            self.elapsed = 0;
+            self.pc += 1;
+
+            let regs = &mut self.regs;
+            match state.instr {
+                Instr::NoOp => (),
+                Instr::Add { reg, value } => {
+                    regs[reg as usize] = regs[reg as usize].wrapping_add(value.value(regs))
+                }
+                Instr::Mul { reg, value } => {
+                    regs[reg as usize] = regs[reg as usize].wrapping_mul(value.value(regs))
+                }
+                Instr::Set { reg, value } => regs[reg as usize] = value.value(regs),
+                Instr::Jump { cond, target } => {
+                    if cond.holds(regs) {
+                        self.pc = target;
+                    }
+                }
+            }
+        }
+
+        Some(state)
+    }
+
+    /// Step through the rest of the program, cycle by cycle, ignoring any
+    /// breakpoints. This never ends if the program doesn't.
+    pub fn trace(&mut self) -> impl Iterator<Item = State> + use<'_, 'a> {
+        iter::from_fn(|| self.step())
+    }
+
+    /// Returns the breakpoint's index.
+    pub fn add_breakpoint(&mut self, breakpoint: Breakpoint) -> usize {
+        self.breakpoints.push(breakpoint);
+        self.breakpoints.len() - 1
+    }
+
+    pub fn clear_breakpoints(&mut self) {
+        self.breakpoints.clear();
+    }
+
+    /// Run until the program ends, or we're about to run a cycle that matches
+    /// a breakpoint. Calling `run` again carries on from there.
+    pub fn run(&mut self) -> Stop {
+        loop {
+            let Some(state) = self.peek() else {
+                return Stop::Halted;
+            };
+
+            if !self.paused {
+                if let Some(i) = self.breakpoints.iter().position(|&b| self.hits(b, state)) {
+                    self.paused = true;
+                    return Stop::Breakpoint(i, state);
+                }
+            }
+
+            self.step();
+        }
+    }
+
+    /// Helper for `run`.
+    fn hits(&self, breakpoint: Breakpoint, state: State) -> bool {
+        match breakpoint {
+            Breakpoint::Cycle(cycle) => state.cycle == cycle,
+            Breakpoint::Pc(pc) => state.pc == pc && state.elapsed == 1,
+            Breakpoint::Reg(reg, ord, value) => {
+                let matches = |regs: Regs| regs[reg as usize].cmp(&value) == ord;
+                matches(state.regs) && !self.prev_regs.is_some_and(matches)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use anyhow::Result;
+    use itertools::Itertools;
+
+    use super::{Breakpoint, Cpu, Stop};
+    use crate::{
+        common::input,
+        y2022::day_10::{parse_input, Costs, Instr, Op, Operand, Reg},
+    };
+
+    fn program(input: &str) -> Result<Vec<Instr>> {
+        parse_input(input)
+    }
+
+    #[test]
+    fn small_example() -> Result<()> {
+        let program = program("noop\naddx 3\naddx -5")?;
+        let trace: Vec<_> = Cpu::new(&program).trace().collect();
+
+        let summary: Vec<_> = trace.iter().map(|s| (s.cycle, s.pc, s.x())).collect();
+        assert_eq!(
+            summary,
+            [(1, 0, 1), (2, 1, 1), (3, 1, 1), (4, 2, 4), (5, 2, 4)]
+        );
+        assert_eq!(
+            trace[2].instr,
+            Instr::Add {
+                reg: Reg::X,
+                value: Operand::Imm(3),
+            }
+        );
+        assert_eq!(trace[2].elapsed, 2);
+
+        let mut cpu = Cpu::new(&program);
+        assert_eq!(cpu.trace().count(), 5);
+        assert!(cpu.is_halted());
+        assert_eq!(cpu.x(), -1);
+        assert_eq!(cpu.cycle(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn breakpoints() -> Result<()> {
+        let program = program("noop\naddx 3\naddx -5\nnoop\naddx 3\nnoop")?;
+        let mut cpu = Cpu::new(&program);
+        cpu.add_breakpoint(Breakpoint::Cycle(3));
+        cpu.add_breakpoint(Breakpoint::Pc(2));
+        cpu.add_breakpoint(Breakpoint::Reg(Reg::X, Ordering::Less, 0));
+        cpu.add_breakpoint(Breakpoint::Reg(Reg::X, Ordering::Equal, 2));
+
+        let mut stops = vec![];
+        loop {
+            match cpu.run() {
+                Stop::Halted => break,
+                Stop::Breakpoint(i, state) => stops.push((i, state.cycle, state.pc, state.x())),
+            }
+        }
+
+        assert_eq!(
+            stops,
+            [
+                (0, 3, 1, 1),
+                // Only once for the two-cycle addx.
+                (1, 4, 2, 4),
+                // Only once while X stays negative.
+                (2, 6, 3, -1),
+                (3, 9, 5, 2),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn stepping_matches_running() -> Result<()> {
+        let program = program(input!(2022, 10))?;
+        let trace: Vec<_> = Cpu::new(&program).trace().collect();
+
+        let mut cpu = Cpu::new(&program);
+        for state in &trace {
+            assert_eq!(cpu.peek(), Some(*state));
+            cpu.add_breakpoint(Breakpoint::Cycle(state.cycle + 1));
+            cpu.run();
+        }
+        assert_eq!(cpu.run(), Stop::Halted);
+        Ok(())
+    }
+
+    #[test]
+    fn control_flow() -> Result<()> {
+        let factorial = program(
+            "
+            setx 1
+            sety 5
+            loop:
+            mulx y
+            addy -1
+            jnzy loop
+            ",
+        )?;
+
+        let mut cpu = Cpu::new(&factorial);
+        let trace: Vec<_> = cpu.trace().collect();
+        assert_eq!(cpu.x(), 120);
+        assert_eq!(cpu.reg(Reg::Y), 0);
+        // 1 + 1, and then 3 + 2 + 2 per loop.
+        assert_eq!(trace.len(), 2 + 5 * 7);
+
+        // A jump's condition is checked on its last cycle.
+        let mut costs = Costs::default();
+        costs.set(Op::Jump, 1)?;
+        assert!(costs.set(Op::Jump, 0).is_err());
+        let mut cpu = Cpu::with_costs(&factorial, costs);
+        cpu.add_breakpoint(Breakpoint::Pc(2));
+        let mut loops = 0;
+        while let Stop::Breakpoint(_, state) = cpu.run() {
+            assert_eq!(state.reg(Reg::Y), 5 - loops);
+            loops += 1;
+        }
+        assert_eq!(loops, 5);
+        assert_eq!(cpu.cycle() - 1, 2 + 5 * 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn jumps() -> Result<()> {
+        let program = program(
+            "
+            jzx skip    # X starts at 1, so we don't jump.
+            setx 0
+            jzx skip
+            setx 99
+            skip: addx 7
+            jmp end
+            setx 99
+            end:
+            ",
+        )?;
+        let mut cpu = Cpu::new(&program);
+        let pcs: Vec<_> = cpu.trace().map(|s| s.pc).dedup().collect();
+        assert_eq!(pcs, [0, 1, 2, 4, 5]);
+        assert_eq!(cpu.x(), 7);
+        Ok(())
+    }
+
+    #[test]
+    fn registers_wrap() -> Result<()> {
+        let program = program("setx 2147483647\naddx 1\nsety -65536\nmuly y")?;
+        let mut cpu = Cpu::new(&program);
+        cpu.trace().for_each(drop);
+        assert_eq!(cpu.x(), i32::MIN);
+        assert_eq!(cpu.reg(Reg::Y), 0);
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use anyhow::{bail, ensure, Result};
+
+/// The puzzle's capital letters, each 4 pixels wide and 6 tall. Not every
+/// letter turns up in Advent of Code, so some are missing.
+const FONT: [(char, [&str; 6]); 18] = [
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', [".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+    (' ', ["....", "....", "....", "....", "....", "...."]),
+];
+
+/// Read the letters off the screen drawn by `draw_screen`.
+///
+/// Each letter sits in a 5-pixel-wide cell: 4 columns of glyph, and then a
+/// blank one. The blank column is optional for the last cell, so a 39-pixel
+/// screen still reads. Fails on a glyph that isn't in the font, showing what
+/// it looks like.
+pub fn read_screen(screen: &[String]) -> Result<String> {
+    ensure!(
+        screen.len() == 6,
+        "the screen should be 6 rows tall, not {}",
+        screen.len()
+    );
+    let w = screen[0].len();
+    ensure!(
+        screen.iter().all(|row| row.len() == w && row.is_ascii()),
+        "the rows of the screen should all be the same length, in ASCII"
+    );
+    ensure!(
+        matches!(w % 5, 0 | 4),
+        "the screen is {w} pixels wide, which doesn't split into 5-pixel cells"
+    );
+
+    let mut text = String::new();
+    for (n, j) in (0..w).step_by(5).enumerate() {
+        let glyph: Vec<_> = screen.iter().map(|row| &row[j..j + 4]).collect();
+        let spacer = screen.iter().filter_map(|row| row.get(j + 4..j + 5));
+
+        let letter = FONT.iter().find(|(_, bitmap)| glyph == bitmap);
+        match letter {
+            Some(&(c, _)) if spacer.clone().all(|p| p == ".") => text.push(c),
+            _ => {
+                let cells: Vec<_> = screen.iter().map(|row| &row[j..(j + 5).min(w)]).collect();
+                bail!("unknown glyph in cell {n}:\n{}", cells.join("\n"))
+            }
+        }
+    }
+
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_screen, FONT};
+
+    fn screen(rows: [&str; 6]) -> Vec<String> {
+        rows.map(String::from).to_vec()
+    }
+
+    #[test]
+    fn letters() {
+        // Every letter in the font, in a 5-pixel cell.
+        let rows: Vec<_> = (0..6)
+            .map(|i| {
+                FONT.iter()
+                    .map(|(_, bitmap)| bitmap[i].to_owned() + ".")
+                    .collect()
+            })
+            .collect();
+        let all: String = FONT.iter().map(|&(c, _)| c).collect();
+        assert_eq!(read_screen(&rows).unwrap(), all);
+
+        // Without the last spacer.
+        let hi = screen([
+            "#..#..###",
+            "#..#...#.",
+            "####...#.",
+            "#..#...#.",
+            "#..#...#.",
+            "#..#..###",
+        ]);
+        assert_eq!(read_screen(&hi).unwrap(), "HI");
+    }
+
+    #[test]
+    fn unknown_glyphs() {
+        let blob = screen(["####.", "#..#.", "####.", "#..#.", "#..#.", "#..##"]);
+        let err = read_screen(&blob).unwrap_err().to_string();
+        assert_eq!(
+            err,
+            "unknown glyph in cell 0:\n####.\n#..#.\n####.\n#..#.\n#..#.\n#..##"
+        );
+
+        let q = screen(["####.", "#..#.", "#..#.", "#..#.", "#.##.", "####."]);
+        assert!(read_screen(&q).is_err());
+        assert!(read_screen(&q[..5]).is_err());
+        assert!(read_screen(&screen(["###"; 6])).is_err());
+    }
+}
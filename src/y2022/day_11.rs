@@ -1,31 +1,25 @@
 use std::{cmp::Reverse, mem};
 
-use anyhow::Result;
 use itertools::Itertools;
 
-use crate::input;
-
-#[test]
-fn part_1() -> Result<()> {
-    let _input = input!(11);
-
-    let answer = Monkeys::new(Part::Part1).play_game(20);
-    dbg!(answer);
-
-    Ok(())
+/// The level of monkey business after 20 rounds, with relief.
+///
+/// The monkeys are hardcoded, so there's no input to parse.
+pub fn part_1() -> usize {
+    Monkeys::new(Part::Part1).play_game(20)
 }
 
-#[test]
-fn part_2() -> Result<()> {
-    let answer = Monkeys::new(Part::Part2).play_game(10_000);
-    dbg!(answer);
-
-    Ok(())
+/// The level of monkey business after 10,000 rounds, without relief.
+pub fn part_2() -> usize {
+    Monkeys::new(Part::Part2).play_game(10_000)
 }
 
+/// Arguments to `Monkey::new`.
+type MonkeyArgs = (Vec<u64>, fn(u64) -> u64, u64, usize, usize);
+
 /// Yes, I really just typed this out by hand.
 fn hardcoded_monkeys() -> [Monkey; 8] {
-    let args: [(_, fn(u64) -> u64, _, _, _); 8] = [
+    let args: [MonkeyArgs; 8] = [
         (vec![57], |x| x * 13, 11, 3, 2),
         (vec![58, 93, 88, 81, 72, 73, 65], |x| x + 2, 7, 6, 7),
         (vec![65, 95], |x| x + 6, 13, 3, 5),
@@ -39,20 +33,21 @@ fn hardcoded_monkeys() -> [Monkey; 8] {
     args.map(|(a, b, c, d, e)| Monkey::new(a, b, c, d, e))
 }
 
-struct Monkeys {
+pub struct Monkeys {
     monkeys: [Monkey; 8],
     part: Part,
     /// Used in part 2.
     divisor_product: u64,
 }
 
-enum Part {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
     Part1,
     Part2,
 }
 
 impl Monkeys {
-    fn new(part: Part) -> Self {
+    pub fn new(part: Part) -> Self {
         let monkeys = hardcoded_monkeys();
         Self {
             divisor_product: monkeys.iter().map(|m| m.test_divisible_by).product(),
@@ -62,7 +57,7 @@ impl Monkeys {
     }
 
     /// Return the level of monkey business after this many rounds.
-    fn play_game(mut self, num_rounds: usize) -> usize {
+    pub fn play_game(mut self, num_rounds: usize) -> usize {
         for _ in 0..num_rounds {
             self.round();
         }
@@ -88,7 +83,7 @@ impl Monkeys {
                 Part::Part2 => new_item %= self.divisor_product,
             }
 
-            let target = if new_item % m.test_divisible_by == 0 {
+            let target = if new_item.is_multiple_of(m.test_divisible_by) {
                 m.true_target
             } else {
                 m.false_target
@@ -137,3 +132,18 @@ impl Monkey {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn part_1() {
+        let answer = super::part_1();
+        dbg!(answer);
+    }
+
+    #[test]
+    fn part_2() {
+        let answer = super::part_2();
+        dbg!(answer);
+    }
+}
@@ -2,47 +2,36 @@ use std::collections::{HashSet, VecDeque};
 
 use anyhow::{ensure, Context, Result};
 
-use crate::input;
-
-#[test]
-fn part_1() -> Result<()> {
-    let input = input!(12);
+use crate::common::{grid::parse_grid, helpers::checked_add};
 
+/// The fewest steps from the start to the end.
+pub fn part_1(input: &str) -> Result<usize> {
     let (grid, start, end) = Grid::parse(input)?;
-    let dist = grid.shortest_path(start, end).context("no path exists")?;
-    dbg!(dist);
-
-    Ok(())
+    grid.shortest_path(start, end).context("no path exists")
 }
 
-#[test]
-fn part_2() -> Result<()> {
-    let input = input!(12);
-
+/// The fewest steps from any square of elevation 'a' to the end.
+pub fn part_2(input: &str) -> Result<usize> {
     let (grid, _start, end) = Grid::parse(input)?;
-    let dist = grid.shortest_path_2(end).context("no path exists")?;
-    dbg!(dist);
-
-    Ok(())
+    grid.shortest_path_2(end).context("no path exists")
 }
 
 /// A non-empty, rectangular grid.
 #[derive(Debug)]
-struct Grid {
+pub struct Grid {
     grid: Vec<Vec<u8>>,
 }
 
-type Point = (usize, usize);
+/// (row, col)
+pub type Point = (usize, usize);
 
 impl Grid {
-    fn parse(s: &str) -> Result<(Self, Point, Point)> {
-        let mut grid: Vec<_> = s.lines().map(|l| l.to_owned().into_bytes()).collect();
-
-        ensure!(!grid.is_empty(), "no rows");
-        ensure!(!grid[0].is_empty(), "empty row");
-        for row in &grid {
-            ensure!(row.len() == grid[0].len(), "jagged grid");
-        }
+    /// Return the grid, along with the start and end points.
+    pub fn parse(s: &str) -> Result<(Self, Point, Point)> {
+        let mut grid = parse_grid(s, |c| {
+            ensure!(c.is_ascii(), "non-ASCII char {c:?}");
+            Ok(c as u8)
+        })?;
 
         // Find the special "start" and "end" markers.
         let mut start = None;
@@ -72,14 +61,15 @@ impl Grid {
         ))
     }
 
-    fn dims(&self) -> Point {
+    /// (height, width)
+    pub fn dims(&self) -> Point {
         let h = self.grid.len();
         let w = self.grid[0].len();
         (h, w)
     }
 
     /// Return None if no path exists.
-    fn shortest_path(&self, start: Point, end: Point) -> Option<usize> {
+    pub fn shortest_path(&self, start: Point, end: Point) -> Option<usize> {
         let mut q = VecDeque::new();
         let mut seen = HashSet::new();
 
@@ -104,7 +94,7 @@ impl Grid {
     }
 
     /// Find the shortest path starting from any 'a', and ending at `end`.
-    fn shortest_path_2(&self, end: Point) -> Option<usize> {
+    pub fn shortest_path_2(&self, end: Point) -> Option<usize> {
         let mut q = VecDeque::new();
         let mut seen = HashSet::new();
 
@@ -153,12 +143,23 @@ impl Grid {
     }
 }
 
-/// Return None if the result would be negative.
-fn checked_add(x: usize, y: isize) -> Option<usize> {
-    let z = (x as isize) + y;
-    if z >= 0 {
-        Some(z as usize)
-    } else {
-        None
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::input;
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let dist = super::part_1(input!(2022, 12))?;
+        dbg!(dist);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let dist = super::part_2(input!(2022, 12))?;
+        dbg!(dist);
+        Ok(())
     }
 }
@@ -4,24 +4,20 @@ use std::{cmp::Ordering, iter::zip};
 
 use anyhow::{ensure, Context, Result};
 
-use crate::input;
-
-#[test]
-fn part_1() -> Result<()> {
-    let input = input!(13);
+/// The sum of the (1-based) indices of the pairs that are in the right order.
+pub fn part_1(input: &str) -> Result<usize> {
     let packets = read_input(input)?;
 
-    let ans: usize = zip(1.., packets)
+    let ans = zip(1.., packets)
         .map(|(i, (p1, p2))| if p1 <= p2 { i } else { 0 })
         .sum();
-    dbg!(ans);
 
-    Ok(())
+    Ok(ans)
 }
 
-#[test]
-fn part_2() -> Result<()> {
-    let input = input!(13);
+/// The decoder key: the product of the (1-based) indices of the two divider
+/// packets, once all packets are sorted.
+pub fn part_2(input: &str) -> Result<usize> {
     let packets = read_input(input)?;
 
     let d1: Packet = "[[2]]".parse()?;
@@ -38,12 +34,10 @@ fn part_2() -> Result<()> {
     let (i2, _) = packets.iter().enumerate().find(|(_, p)| **p == d2).unwrap();
 
     let ans = (i1 + 1) * (i2 + 1);
-    dbg!(ans);
-
-    Ok(())
+    Ok(ans)
 }
 
-fn read_input(input: &str) -> Result<Vec<(Packet, Packet)>> {
+pub fn read_input(input: &str) -> Result<Vec<(Packet, Packet)>> {
     let mut out = vec![];
     let mut lines = input.lines();
 
@@ -69,7 +63,7 @@ fn read_input(input: &str) -> Result<Vec<(Packet, Packet)>> {
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-enum Packet {
+pub enum Packet {
     List(Vec<Packet>),
     Int(i32),
 }
@@ -89,7 +83,7 @@ impl Ord for Packet {
                 l1.len().cmp(&l2.len())
             }
 
-            (Self::Int(x), Self::Int(y)) => x.cmp(&y),
+            (Self::Int(x), Self::Int(y)) => x.cmp(y),
 
             (Self::Int(x), Self::List(_)) => {
                 let list_self = Packet::List(vec![Packet::Int(*x)]);
@@ -109,3 +103,24 @@ impl PartialOrd for Packet {
         Some(self.cmp(other))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::input;
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let ans = super::part_1(input!(2022, 13))?;
+        dbg!(ans);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let ans = super::part_2(input!(2022, 13))?;
+        dbg!(ans);
+        Ok(())
+    }
+}
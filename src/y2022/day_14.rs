@@ -3,40 +3,43 @@ use std::{
     ops::Add,
 };
 
+use anyhow::{ensure, Context, Result};
 use itertools::Itertools;
 
-use crate::input;
-
-#[test]
-fn part_1() {
-    let input = input!(14);
-    let paths = parse_input(input);
+/// The number of units of sand that come to rest before sand starts flowing
+/// into the abyss.
+pub fn part_1(input: &str) -> Result<usize> {
+    let paths = parse_input(input)?;
 
     let mut grid = Grid::new(&paths);
     let sim = grid.simulate(Point { x: 500, y: 0 });
-    assert!(matches!(sim, Simulate::OutOfBounds));
+    ensure!(
+        matches!(sim, Simulate::OutOfBounds),
+        "expected sand to fall out of bounds"
+    );
 
-    let ans = grid.count_sand();
-    dbg!(ans);
+    Ok(grid.count_sand())
 }
 
-#[test]
-fn part_2() {
-    let input = input!(14);
-    let paths = parse_input(input);
+/// The number of units of sand that come to rest before the source is blocked,
+/// given an infinite floor.
+pub fn part_2(input: &str) -> Result<usize> {
+    let paths = parse_input(input)?;
 
     let mut grid = Grid::with_floor(&paths);
     let sim = grid.simulate(Point { x: 500, y: 0 });
-    assert!(matches!(sim, Simulate::SourceBlocked));
+    ensure!(
+        matches!(sim, Simulate::SourceBlocked),
+        "expected the sand source to be blocked"
+    );
 
-    let ans = grid.count_sand();
-    dbg!(ans);
+    Ok(grid.count_sand())
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-struct Point {
-    x: isize,
-    y: isize,
+pub struct Point {
+    pub x: isize,
+    pub y: isize,
 }
 
 impl From<(isize, isize)> for Point {
@@ -56,25 +59,30 @@ impl Add for Point {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Vec<Point>> {
-    input.lines().map(parse_line).collect()
+/// Each rock path is a non-empty list of points.
+pub fn parse_input(input: &str) -> Result<Vec<Vec<Point>>> {
+    let paths: Vec<_> = input.lines().map(parse_line).collect::<Result<_>>()?;
+    ensure!(!paths.is_empty(), "no rock paths");
+    Ok(paths)
 }
 
-fn parse_line(line: &str) -> Vec<Point> {
+fn parse_line(line: &str) -> Result<Vec<Point>> {
     line.split(" -> ").map(parse_point).collect()
 }
 
-fn parse_point(word: &str) -> Point {
+fn parse_point(word: &str) -> Result<Point> {
     let (x, y) = word
         .split(',')
-        .map(|n| n.parse().unwrap())
         .collect_tuple()
-        .unwrap();
-    Point { x, y }
+        .with_context(|| format!("expected x,y: {word:?}"))?;
+    let x = x.parse()?;
+    let y = y.parse()?;
+    ensure!(x >= 0 && y >= 0, "negative coordinates: {word:?}");
+    Ok(Point { x, y })
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
-enum Cell {
+pub enum Cell {
     Air,
     Rock,
     Sand,
@@ -90,7 +98,7 @@ impl Debug for Cell {
     }
 }
 
-struct Grid {
+pub struct Grid {
     /// Rectangular, non-empty.
     ///
     /// Indexed as `self.cells[y][x]`.
@@ -110,8 +118,9 @@ impl Debug for Grid {
 }
 
 impl Grid {
-    fn new(paths: &[Vec<Point>]) -> Self {
-        let points = paths.iter().flat_map(|path| path.iter().map(|&p| p));
+    /// Panics if `paths` is empty.
+    pub fn new(paths: &[Vec<Point>]) -> Self {
+        let points = paths.iter().flat_map(|path| path.iter().copied());
         let x_max = points.clone().map(|p| p.x).max().unwrap();
         let y_max = points.clone().map(|p| p.y).max().unwrap();
         let h = y_max as usize + 1;
@@ -123,7 +132,8 @@ impl Grid {
         this
     }
 
-    fn with_floor(paths: &[Vec<Point>]) -> Self {
+    /// Like `new`, but with a floor two rows below the lowest rock.
+    pub fn with_floor(paths: &[Vec<Point>]) -> Self {
         let mut this = Self::new(paths);
         let w = this.dims().x as usize;
         this.cells.push(vec![Cell::Air; w]);
@@ -156,7 +166,7 @@ impl Grid {
         }
     }
 
-    fn dims(&self) -> Point {
+    pub fn dims(&self) -> Point {
         let y = self.cells.len() as isize;
         let x = self.cells[0].len() as isize;
         Point { x, y }
@@ -167,7 +177,7 @@ impl Grid {
         0 <= p.x && p.x < dims.x && 0 <= p.y && p.y < dims.y
     }
 
-    fn get(&self, p: Point) -> Cell {
+    pub fn get(&self, p: Point) -> Cell {
         assert!(self.in_bounds(p));
         self.cells[p.y as usize][p.x as usize]
     }
@@ -177,7 +187,8 @@ impl Grid {
         &mut self.cells[p.y as usize][p.x as usize]
     }
 
-    fn simulate(&mut self, sand_source: Point) -> Simulate {
+    /// Drop sand from `sand_source` until it stops coming to rest.
+    pub fn simulate(&mut self, sand_source: Point) -> Simulate {
         assert!(self.in_bounds(sand_source));
         loop {
             match self.drop_sand(sand_source) {
@@ -224,7 +235,7 @@ impl Grid {
         StepSand::Stuck
     }
 
-    fn count_sand(&self) -> usize {
+    pub fn count_sand(&self) -> usize {
         let sand = self
             .cells
             .iter()
@@ -233,7 +244,8 @@ impl Grid {
     }
 }
 
-enum Simulate {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Simulate {
     SourceBlocked,
     OutOfBounds,
 }
@@ -249,3 +261,24 @@ enum StepSand {
     OutOfBounds,
     MovedTo(Point),
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::input;
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let ans = super::part_1(input!(2022, 14))?;
+        dbg!(ans);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let ans = super::part_2(input!(2022, 14))?;
+        dbg!(ans);
+        Ok(())
+    }
+}
@@ -1,64 +1,61 @@
 use std::collections::HashSet;
 
+use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::input;
-
-#[test]
-fn part_1() {
-    let input = input!(15);
-    let sensors = parse_input(input);
-    let ans = num_non_beacons(&sensors);
-    dbg!(ans);
+/// The number of positions in the row y=2,000,000 that can't contain a beacon.
+pub fn part_1(input: &str) -> Result<usize> {
+    let sensors = parse_input(input)?;
+    Ok(num_non_beacons(&sensors))
 }
 
-#[test]
-fn part_2() {
-    let input = input!("15");
-    let sensors = parse_input(input);
-    let ans = find_the_one(&sensors);
-    dbg!(ans);
+/// The only position in the search area that could contain the distress beacon.
+pub fn part_2(input: &str) -> Result<Point> {
+    let sensors = parse_input(input)?;
+    find_the_one(&sensors).context("no uncovered position")
 }
 
-#[derive(Clone, Copy, Debug)]
-struct Point {
-    x: i32,
-    y: i32,
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
 }
 
 #[derive(Debug, Copy, Clone)]
-struct Sensor {
-    sensor: Point,
-    nearest_beacon: Point,
+pub struct Sensor {
+    pub sensor: Point,
+    pub nearest_beacon: Point,
 }
 
-fn parse_input(input: &str) -> Vec<Sensor> {
+pub fn parse_input(input: &str) -> Result<Vec<Sensor>> {
     input.lines().map(parse_line).collect()
 }
 
-fn parse_line(line: &str) -> Sensor {
+fn parse_line(line: &str) -> Result<Sensor> {
     static RE: Lazy<Regex> = Lazy::new(|| {
         let n = r"(-?\d+)";
         let re = format!(r"^Sensor at x={n}, y={n}: closest beacon is at x={n}, y={n}$");
         Regex::new(&re).unwrap()
     });
 
-    let caps = RE.captures(line).unwrap();
-    let x = caps[1].parse().unwrap();
-    let y = caps[2].parse().unwrap();
+    let caps = RE
+        .captures(line)
+        .with_context(|| format!("not a sensor report: {line:?}"))?;
+    let x = caps[1].parse()?;
+    let y = caps[2].parse()?;
     let sensor = Point { x, y };
-    let x = caps[3].parse().unwrap();
-    let y = caps[4].parse().unwrap();
+    let x = caps[3].parse()?;
+    let y = caps[4].parse()?;
     let nearest_beacon = Point { x, y };
 
-    Sensor {
+    Ok(Sensor {
         sensor,
         nearest_beacon,
-    }
+    })
 }
 
-fn num_non_beacons(sensors: &[Sensor]) -> usize {
+pub fn num_non_beacons(sensors: &[Sensor]) -> usize {
     let y = 2_000_000;
 
     // Shadow and beacons on the row we care about (y=2M)
@@ -82,34 +79,25 @@ fn num_non_beacons(sensors: &[Sensor]) -> usize {
     shadow.len() - beacons.len()
 }
 
-fn find_the_one(sensors: &[Sensor]) -> Point {
-    // let mut points = vec![];
-    for sensor in sensors {
-        let bounding_box = bounding_box(sensor);
-        for point in bounding_box {
-            if !intersects_boxes(point, sensors) {
-                return point;
-            }
-        }
-    }
-
-    // for point in points {
-    //     dbg!(point);
-    // }
-
-    panic!("AHHHHHHH!!");
+/// The first position just outside some sensor's range that no sensor covers,
+/// if there is one.
+pub fn find_the_one(sensors: &[Sensor]) -> Option<Point> {
+    sensors
+        .iter()
+        .flat_map(bounding_box)
+        .find(|&point| !intersects_boxes(point, sensors))
 }
 
 // #[test]
 // fn check() {
-//     let input = input!("15-small");
+//     let input = input!(2022, "15-small");
 //     let sensors = parse_input(input);
 //     assert!(!intersects_boxes(Point{x: 14, y: 11}, &sensors));
 // }
 
 // #[test]
 // fn check_bb() {
-//     let input = input!("15-small");
+//     let input = input!(2022, "15-small");
 //     let sensors = parse_input(input);
 //     let bb = bounding_box(&Sensor {sensor: Point {x: 8, y: 7},
 //                                    nearest_beacon: Point {x: 2, y: 10}});
@@ -138,7 +126,7 @@ fn intersects_boxes(point: Point, sensors: &[Sensor]) -> bool {
     //     dbg!(beacon_rad);
     // }
 
-    return false;
+    false
 }
 
 fn bounding_box(sensor: &Sensor) -> Vec<Point> {
@@ -151,7 +139,7 @@ fn bounding_box(sensor: &Sensor) -> Vec<Point> {
         // line segment /
         let x_bound = x - (dist + diff);
         let y_bound = y + diff;
-        if x_bound < 0 || x_bound > K_UB || y_bound < 0 || y_bound > K_UB {
+        if !(0..=K_UB).contains(&x_bound) || !(0..=K_UB).contains(&y_bound) {
             continue;
         }
 
@@ -165,7 +153,7 @@ fn bounding_box(sensor: &Sensor) -> Vec<Point> {
         // line segment \
         let x_bound = x + (dist - diff);
         let y_bound = y + diff;
-        if x_bound < 0 || x_bound > K_UB || y_bound < 0 || y_bound > K_UB {
+        if !(0..=K_UB).contains(&x_bound) || !(0..=K_UB).contains(&y_bound) {
             continue;
         }
 
@@ -179,7 +167,7 @@ fn bounding_box(sensor: &Sensor) -> Vec<Point> {
         // line segment \ (but underneath)
         let x_bound = x - (dist + diff);
         let y_bound = y - diff;
-        if x_bound < 0 || x_bound > K_UB || y_bound < 0 || y_bound > K_UB {
+        if !(0..=K_UB).contains(&x_bound) || !(0..=K_UB).contains(&y_bound) {
             continue;
         }
 
@@ -193,7 +181,7 @@ fn bounding_box(sensor: &Sensor) -> Vec<Point> {
         // line segment / (but underneath)
         let x_bound = x + (dist - diff);
         let y_bound = y - diff;
-        if x_bound < 0 || x_bound > K_UB || y_bound < 0 || y_bound > K_UB {
+        if !(0..=K_UB).contains(&x_bound) || !(0..=K_UB).contains(&y_bound) {
             continue;
         }
 
@@ -226,3 +214,31 @@ fn draw_shadow(center: i32, diam: i32, out: &mut HashSet<i32>) {
         out.insert(center - i);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::input;
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let ans = super::part_1(input!(2022, 15))?;
+        dbg!(ans);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let ans = super::part_2(input!(2022, "15"))?;
+        dbg!(ans);
+        Ok(())
+    }
+
+    #[test]
+    fn no_uncovered_position() {
+        // One sensor covers the whole search area.
+        let input = "Sensor at x=0, y=0: closest beacon is at x=8000000, y=0";
+        assert!(super::part_2(input).is_err());
+    }
+}
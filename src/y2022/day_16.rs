@@ -1,29 +1,19 @@
 use std::collections::{HashMap, HashSet};
 
-use anyhow::Context;
+use anyhow::{Context, Result};
 use once_cell::sync::Lazy;
 use regex::Regex;
 
-use crate::input;
-
-#[test]
-fn part_1() {
-    let input = input!(16);
-    let graph = parse_input(input);
-    assert!(graph.is_undirected());
-    // dbg!(&graph, graph.nodes.len());
-}
-
-fn parse_input(input: &str) -> Graph {
+pub fn parse_input(input: &str) -> Result<Graph> {
     let mut nodes = HashMap::new();
     for line in input.lines() {
-        let node = parse_line(line);
+        let node = parse_line(line)?;
         nodes.insert(node.name.clone(), node);
     }
-    Graph { nodes }
+    Ok(Graph { nodes })
 }
 
-fn parse_line(line: &str) -> Node {
+fn parse_line(line: &str) -> Result<Node> {
     static RE: Lazy<Regex> = Lazy::new(|| {
         let label = r"([A-Z]+)";
         let num = r"(\d+)";
@@ -34,34 +24,36 @@ fn parse_line(line: &str) -> Node {
 
     let caps = RE
         .captures(line)
-        .with_context(|| format!("no match\n\tline: {line:?}\n\tregex: {RE:?}"))
-        .unwrap();
+        .with_context(|| format!("no match\n\tline: {line:?}\n\tregex: {RE:?}"))?;
 
     let name = caps[1].to_owned();
-    let flow_rate = caps[2].parse().unwrap();
+    let flow_rate = caps[2].parse()?;
     let neighbors = caps[3].split(", ").map(str::to_owned).collect();
 
-    Node {
+    Ok(Node {
         name,
         flow_rate,
         neighbors,
-    }
+    })
 }
 
-struct Graph {
-    nodes: HashMap<String, Node>,
+#[derive(Debug)]
+pub struct Graph {
+    /// Keyed by valve name.
+    pub nodes: HashMap<String, Node>,
 }
 
 #[derive(Debug)]
-struct Node {
-    name: String,
-    flow_rate: u32,
-    neighbors: HashSet<String>,
+pub struct Node {
+    pub name: String,
+    pub flow_rate: u32,
+    pub neighbors: HashSet<String>,
 }
 
 impl Graph {
-    fn is_undirected(&self) -> bool {
-        fn dfs<'a, 'b>(g: &'a Graph, curr: &'a str, seen: &'b mut HashSet<&'a str>) -> bool {
+    /// Also checks that the graph is connected.
+    pub fn is_undirected(&self) -> bool {
+        fn dfs<'a>(g: &'a Graph, curr: &'a str, seen: &mut HashSet<&'a str>) -> bool {
             if seen.contains(curr) {
                 return true;
             }
@@ -119,3 +111,19 @@ impl Graph {
 //     time_remaining: u8,
 //     opened: HashSet<String>,
 // }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::input;
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let input = input!(2022, 16);
+        let graph = super::parse_input(input)?;
+        assert!(graph.is_undirected());
+        // dbg!(&graph, graph.nodes.len());
+        Ok(())
+    }
+}
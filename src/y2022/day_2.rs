@@ -0,0 +1,175 @@
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+/// The total score if the second column is the shape to play.
+pub fn part_1(input: &str) -> Result<u32> {
+    let rounds = parse_input(input)?;
+    let total = rounds
+        .iter()
+        .map(|r| {
+            let mine = r.response.as_shape();
+            mine.score() + Outcome::of(mine, r.opponent).score()
+        })
+        .sum();
+    Ok(total)
+}
+
+/// The total score if the second column is the desired outcome.
+pub fn part_2(input: &str) -> Result<u32> {
+    let rounds = parse_input(input)?;
+    let total = rounds
+        .iter()
+        .map(|r| {
+            let outcome = r.response.as_outcome();
+            let mine = outcome.response_to(r.opponent);
+            mine.score() + outcome.score()
+        })
+        .sum();
+    Ok(total)
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Round>> {
+    input.lines().map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Result<Round> {
+    let (opponent, response) = line
+        .split_whitespace()
+        .collect_tuple()
+        .with_context(|| format!("expected two words: {line:?}"))?;
+
+    let opponent = match opponent {
+        "A" => Shape::Rock,
+        "B" => Shape::Paper,
+        "C" => Shape::Scissors,
+        _ => bail!("not an opponent code: {opponent:?}"),
+    };
+
+    let response = match response {
+        "X" => Response::X,
+        "Y" => Response::Y,
+        "Z" => Response::Z,
+        _ => bail!("not a response code: {response:?}"),
+    };
+
+    Ok(Round { opponent, response })
+}
+
+/// One line of the strategy guide.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub opponent: Shape,
+    pub response: Response,
+}
+
+/// The second column of the strategy guide. What it means depends on which
+/// part of the puzzle we're solving.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    X,
+    Y,
+    Z,
+}
+
+impl Response {
+    /// Part 1's interpretation.
+    pub fn as_shape(self) -> Shape {
+        match self {
+            Self::X => Shape::Rock,
+            Self::Y => Shape::Paper,
+            Self::Z => Shape::Scissors,
+        }
+    }
+
+    /// Part 2's interpretation.
+    pub fn as_outcome(self) -> Outcome {
+        match self {
+            Self::X => Outcome::Lose,
+            Self::Y => Outcome::Draw,
+            Self::Z => Outcome::Win,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shape {
+    Rock,
+    Paper,
+    Scissors,
+}
+
+impl Shape {
+    pub fn score(self) -> u32 {
+        match self {
+            Self::Rock => 1,
+            Self::Paper => 2,
+            Self::Scissors => 3,
+        }
+    }
+
+    /// The shape that this one defeats.
+    pub fn beats(self) -> Self {
+        match self {
+            Self::Rock => Self::Scissors,
+            Self::Paper => Self::Rock,
+            Self::Scissors => Self::Paper,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Lose,
+    Draw,
+    Win,
+}
+
+impl Outcome {
+    /// The outcome for us, if we play `mine` against `theirs`.
+    pub fn of(mine: Shape, theirs: Shape) -> Self {
+        if mine == theirs {
+            Self::Draw
+        } else if mine.beats() == theirs {
+            Self::Win
+        } else {
+            Self::Lose
+        }
+    }
+
+    /// The shape we need to play against `theirs` to get this outcome.
+    pub fn response_to(self, theirs: Shape) -> Shape {
+        match self {
+            Self::Draw => theirs,
+            Self::Lose => theirs.beats(),
+            // The shape that beats `theirs` is the one `theirs` doesn't beat.
+            Self::Win => theirs.beats().beats(),
+        }
+    }
+
+    pub fn score(self) -> u32 {
+        match self {
+            Self::Lose => 0,
+            Self::Draw => 3,
+            Self::Win => 6,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::example;
+
+    #[test]
+    fn part_1_example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, 2))?, 15);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_example() -> Result<()> {
+        assert_eq!(super::part_2(example!(2022, 2))?, 12);
+        Ok(())
+    }
+}
@@ -0,0 +1,104 @@
+use anyhow::{bail, ensure, Context, Result};
+
+/// The sum of the priorities of the item in both compartments of each
+/// rucksack.
+pub fn part_1(input: &str) -> Result<u32> {
+    let rucksacks = parse_input(input)?;
+
+    let mut sum = 0;
+    for r in rucksacks {
+        let (left, right) = r.compartments();
+        sum += only_item(left & right)?;
+    }
+    Ok(sum)
+}
+
+/// The sum of the priorities of each group of three elves' badge.
+pub fn part_2(input: &str) -> Result<u32> {
+    let rucksacks = parse_input(input)?;
+    ensure!(
+        rucksacks.len() % 3 == 0,
+        "number of rucksacks isn't a multiple of 3: {}",
+        rucksacks.len()
+    );
+
+    let mut sum = 0;
+    for group in rucksacks.chunks(3) {
+        let badge = group.iter().map(Rucksack::items).fold(!0, |a, b| a & b);
+        sum += only_item(badge)?;
+    }
+    Ok(sum)
+}
+
+pub fn parse_input(input: &str) -> Result<Vec<Rucksack>> {
+    input.lines().map(Rucksack::parse).collect()
+}
+
+/// The items in a rucksack, as priorities (1 through 52).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rucksack {
+    /// Even length; the first half is in the first compartment.
+    items: Vec<u32>,
+}
+
+impl Rucksack {
+    pub fn parse(line: &str) -> Result<Self> {
+        let items: Vec<_> = line.chars().map(priority).collect::<Result<_>>()?;
+        ensure!(
+            items.len() % 2 == 0,
+            "odd number of items in rucksack: {line:?}"
+        );
+        Ok(Self { items })
+    }
+
+    /// The set of item priorities in each compartment, as bitsets.
+    pub fn compartments(&self) -> (u64, u64) {
+        let (left, right) = self.items.split_at(self.items.len() / 2);
+        (bitset(left), bitset(right))
+    }
+
+    /// The set of item priorities in the whole rucksack, as a bitset.
+    pub fn items(&self) -> u64 {
+        bitset(&self.items)
+    }
+}
+
+/// Lowercase items have priorities 1 through 26; uppercase 27 through 52.
+pub fn priority(item: char) -> Result<u32> {
+    let p = match item {
+        'a'..='z' => item as u32 - 'a' as u32 + 1,
+        'A'..='Z' => item as u32 - 'A' as u32 + 27,
+        _ => bail!("not an item: {item:?}"),
+    };
+    Ok(p)
+}
+
+fn bitset(priorities: &[u32]) -> u64 {
+    priorities.iter().fold(0, |set, &p| set | 1 << p)
+}
+
+/// Helper for part_1 and part_2: the priority of the one item in `set`.
+fn only_item(set: u64) -> Result<u32> {
+    ensure!(set.count_ones() <= 1, "more than one common item");
+    let p = set.trailing_zeros();
+    (p < 64).then_some(p).context("no common item")
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::example;
+
+    #[test]
+    fn part_1_example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, 3))?, 157);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_example() -> Result<()> {
+        assert_eq!(super::part_2(example!(2022, 3))?, 70);
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use std::ops::RangeInclusive;
+
+use anyhow::{ensure, Context, Result};
+use lazy_regex::regex;
+
+/// The number of pairs where one range fully contains the other.
+pub fn part_1(input: &str) -> Result<usize> {
+    let pairs = parse_input(input)?;
+    let count = pairs
+        .iter()
+        .filter(|(a, b)| contains(a, b) || contains(b, a))
+        .count();
+    Ok(count)
+}
+
+/// The number of pairs where the ranges overlap at all.
+pub fn part_2(input: &str) -> Result<usize> {
+    let pairs = parse_input(input)?;
+    let count = pairs.iter().filter(|(a, b)| overlaps(a, b)).count();
+    Ok(count)
+}
+
+/// A range of section IDs; non-empty.
+pub type Sections = RangeInclusive<u32>;
+
+pub fn parse_input(input: &str) -> Result<Vec<(Sections, Sections)>> {
+    input.lines().map(parse_line).collect()
+}
+
+fn parse_line(line: &str) -> Result<(Sections, Sections)> {
+    let caps = regex!(r"^(\d+)-(\d+),(\d+)-(\d+)$")
+        .captures(line)
+        .with_context(|| format!("not a pair of ranges: {line:?}"))?;
+
+    let a = caps[1].parse()?..=caps[2].parse()?;
+    let b = caps[3].parse()?..=caps[4].parse()?;
+    ensure!(!a.is_empty() && !b.is_empty(), "empty range: {line:?}");
+
+    Ok((a, b))
+}
+
+/// Does `a` fully contain `b`?
+pub fn contains(a: &Sections, b: &Sections) -> bool {
+    a.start() <= b.start() && b.end() <= a.end()
+}
+
+pub fn overlaps(a: &Sections, b: &Sections) -> bool {
+    a.start() <= b.end() && b.start() <= a.end()
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::example;
+
+    #[test]
+    fn part_1_example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, 4))?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2_example() -> Result<()> {
+        assert_eq!(super::part_2(example!(2022, 4))?, 4);
+        Ok(())
+    }
+}
@@ -0,0 +1,244 @@
+mod crane;
+mod parse;
+mod replay;
+
+use std::{
+    fmt::{self, Display},
+    ops::Index,
+};
+
+use anyhow::{ensure, Context, Result};
+use itertools::Itertools;
+
+pub use self::{
+    crane::{Crane, CrateMover9000, CrateMover9001, LimitedCrane},
+    parse::{parse_input, parse_stacks},
+    replay::{Replay, Step},
+};
+
+/// Move crates one at a time, and return the message spelled by the tops of
+/// the stacks.
+pub fn part_1(input: &str) -> Result<String> {
+    solve(input, &CrateMover9000)
+}
+
+/// Move crates several at a time, and return the message spelled by the tops
+/// of the stacks.
+pub fn part_2(input: &str) -> Result<String> {
+    solve(input, &CrateMover9001)
+}
+
+/// Run all the instructions with the given crane, and return the message.
+pub fn solve(input: &str, crane: &impl Crane) -> Result<String> {
+    let (mut stacks, instructions) = parse_input(input)?;
+    stacks.run(crane, &instructions)?;
+    stacks.message()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Instruction {
+    pub amount: usize,
+    /// 0-indexed.
+    pub source: usize,
+    /// 0-indexed.
+    pub dest: usize,
+}
+
+/// Stacks of crates, each listed bottom to top.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Stacks {
+    stacks: Vec<Vec<char>>,
+}
+
+/// One string per stack, listed bottom to top.
+impl<S: AsRef<str>> FromIterator<S> for Stacks {
+    fn from_iter<I: IntoIterator<Item = S>>(iter: I) -> Self {
+        let stacks = iter
+            .into_iter()
+            .map(|s| s.as_ref().chars().collect())
+            .collect();
+        Self { stacks }
+    }
+}
+
+impl Index<usize> for Stacks {
+    type Output = [char];
+
+    fn index(&self, i: usize) -> &[char] {
+        &self.stacks[i]
+    }
+}
+
+/// Draw the stacks the way the puzzle does, with a numbered footer.
+impl Display for Stacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let height = self.stacks.iter().map(Vec::len).max().unwrap_or(0);
+
+        for y in (0..height).rev() {
+            let row = self.stacks.iter().map(|s| match s.get(y) {
+                Some(c) => format!("[{c}]"),
+                None => "   ".to_owned(),
+            });
+            writeln!(f, "{}", row.format(" "))?;
+        }
+
+        let footer = (1..=self.len()).map(|i| format!("{i:^3}"));
+        writeln!(f, "{}", footer.format(" "))
+    }
+}
+
+impl Stacks {
+    pub fn new(stacks: Vec<Vec<char>>) -> Self {
+        Self { stacks }
+    }
+
+    /// The number of stacks.
+    pub fn len(&self) -> usize {
+        self.stacks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &[char]> {
+        self.stacks.iter().map(Vec::as_slice)
+    }
+
+    /// The crates on top of each stack.
+    pub fn message(&self) -> Result<String> {
+        self.stacks
+            .iter()
+            .enumerate()
+            .map(|(i, s)| {
+                s.last()
+                    .with_context(|| format!("stack {} is empty", i + 1))
+            })
+            .collect()
+    }
+
+    /// Run each instruction in turn.
+    pub fn run(&mut self, crane: &impl Crane, instructions: &[Instruction]) -> Result<()> {
+        for (i, &instr) in instructions.iter().enumerate() {
+            self.apply(crane, instr)
+                .with_context(|| format!("instruction {}", i + 1))?;
+        }
+        Ok(())
+    }
+
+    /// Check that `instr` makes sense, and then hand it off to the crane.
+    pub fn apply(&mut self, crane: &impl Crane, instr: Instruction) -> Result<()> {
+        self.validate(instr)?;
+        crane.execute(self, instr)
+    }
+
+    pub fn validate(&self, instr: Instruction) -> Result<()> {
+        let Instruction {
+            amount,
+            source,
+            dest,
+        } = instr;
+        let n = self.len();
+
+        ensure!(amount != 0, "can't move zero crates");
+        ensure!(source < n, "no stack {}; there are {n}", source + 1);
+        ensure!(dest < n, "no stack {}; there are {n}", dest + 1);
+
+        let available = self.stacks[source].len();
+        ensure!(
+            amount <= available,
+            "can't move {amount} crates from stack {}; it has {available}",
+            source + 1
+        );
+
+        Ok(())
+    }
+
+    /// Remove the top `n` crates from stack `i`, keeping their order.
+    pub fn lift(&mut self, i: usize, n: usize) -> Result<Vec<char>> {
+        let stack = self.stacks.get_mut(i).context("no such stack")?;
+        let idx = stack
+            .len()
+            .checked_sub(n)
+            .context("not enough crates on stack")?;
+        Ok(stack.split_off(idx))
+    }
+
+    /// Put `crates` on top of stack `i`, keeping their order.
+    pub fn place(&mut self, i: usize, crates: &[char]) -> Result<()> {
+        let stack = self.stacks.get_mut(i).context("no such stack")?;
+        stack.extend_from_slice(crates);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{parse_input, CrateMover9000, CrateMover9001, Instruction, LimitedCrane};
+    use crate::common::{example, input};
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let message = super::part_1(input!(2022, 5))?;
+        dbg!(message);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let message = super::part_2(input!(2022, 5))?;
+        dbg!(message);
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, 5))?, "CMZ");
+        assert_eq!(super::part_2(example!(2022, 5))?, "MCD");
+        Ok(())
+    }
+
+    #[test]
+    fn limited_crane() -> Result<()> {
+        let input = example!(2022, 5);
+        let solve = |capacity| super::solve(input, &LimitedCrane { capacity });
+
+        // With capacity 1 it's a 9000; with room for everything it's a 9001.
+        assert_eq!(solve(1)?, "CMZ");
+        assert_eq!(solve(3)?, "MCD");
+
+        // The second instruction moves 3 crates, as a lift of 2 and then 1.
+        assert_eq!(solve(2)?, "MCZ");
+        assert!(solve(0).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_instructions() -> Result<()> {
+        let (stacks, _) = parse_input(example!(2022, 5))?;
+        let bad = [
+            // Zero amount.
+            (0, 0, 1),
+            // Out of range.
+            (1, 3, 0),
+            (1, 0, 3),
+            // More crates than the stack has.
+            (3, 0, 1),
+        ];
+
+        for (amount, source, dest) in bad {
+            let instr = Instruction {
+                amount,
+                source,
+                dest,
+            };
+            assert!(stacks.clone().apply(&CrateMover9000, instr).is_err());
+            assert!(stacks.clone().apply(&CrateMover9001, instr).is_err());
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,56 @@
+use anyhow::{ensure, Result};
+
+use super::{Instruction, Stacks};
+
+/// A model of crane, which decides how a `move` instruction plays out.
+pub trait Crane {
+    /// Carry out an instruction that `Stacks::validate` has accepted.
+    fn execute(&self, stacks: &mut Stacks, instr: Instruction) -> Result<()>;
+}
+
+/// Moves one crate at a time, so the moved crates end up reversed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrateMover9000;
+
+impl Crane for CrateMover9000 {
+    fn execute(&self, stacks: &mut Stacks, instr: Instruction) -> Result<()> {
+        for _ in 0..instr.amount {
+            let c = stacks.lift(instr.source, 1)?;
+            stacks.place(instr.dest, &c)?;
+        }
+        Ok(())
+    }
+}
+
+/// Moves all the crates at once, so they keep their order.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CrateMover9001;
+
+impl Crane for CrateMover9001 {
+    fn execute(&self, stacks: &mut Stacks, instr: Instruction) -> Result<()> {
+        let crates = stacks.lift(instr.source, instr.amount)?;
+        stacks.place(instr.dest, &crates)
+    }
+}
+
+/// Moves up to `capacity` crates at once, splitting bigger moves into several
+/// lifts.
+#[derive(Debug, Clone, Copy)]
+pub struct LimitedCrane {
+    pub capacity: usize,
+}
+
+impl Crane for LimitedCrane {
+    fn execute(&self, stacks: &mut Stacks, instr: Instruction) -> Result<()> {
+        ensure!(self.capacity != 0, "crane can't lift anything");
+
+        let mut remaining = instr.amount;
+        while remaining != 0 {
+            let n = remaining.min(self.capacity);
+            let crates = stacks.lift(instr.source, n)?;
+            stacks.place(instr.dest, &crates)?;
+            remaining -= n;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{bail, ensure, Context, Result};
+use lazy_regex::regex;
+
+use super::{Instruction, Stacks};
+
+/// The stacks can be given in either of two forms.
+///
+/// The puzzle's original drawing, with a numbered footer:
+///
+/// ```text
+///     [D]
+/// [N] [C]
+/// [Z] [M] [P]
+///  1   2   3
+/// ```
+///
+/// Or a compact form, with one line per stack, listed bottom to top:
+///
+/// ```text
+/// ZN
+/// MCD
+/// P
+/// ```
+pub fn parse_input(input: &str) -> Result<(Stacks, Vec<Instruction>)> {
+    let (stacks, instructions) = input
+        .split_once("\n\n")
+        .context("expected a blank line between stacks and instructions")?;
+
+    let stacks = parse_stacks(stacks)?;
+    let instructions = instructions
+        .lines()
+        .map(parse_instruction)
+        .collect::<Result<_>>()?;
+
+    Ok((stacks, instructions))
+}
+
+pub fn parse_stacks(s: &str) -> Result<Stacks> {
+    let lines: Vec<_> = s.lines().collect();
+    let Some((footer, rows)) = lines.split_last() else {
+        bail!("no stacks");
+    };
+
+    if regex!(r"^[\s\d]+$").is_match(footer) {
+        parse_drawing(rows, footer)
+    } else {
+        parse_compact(&lines)
+    }
+}
+
+fn parse_compact(lines: &[&str]) -> Result<Stacks> {
+    for (i, line) in lines.iter().enumerate() {
+        ensure!(
+            line.chars().all(|c| c.is_ascii_uppercase()),
+            "stack {}: expected only crate letters, got {line:?}",
+            i + 1
+        );
+    }
+
+    Ok(lines.iter().collect())
+}
+
+/// Each stack occupies a column 4 chars wide: `[X]` plus a space separator.
+///
+/// Rows may be ragged on the right, i.e. trailing empty slots can be omitted.
+fn parse_drawing(rows: &[&str], footer: &str) -> Result<Stacks> {
+    let n = parse_footer(footer)?;
+    let mut stacks = vec![vec![]; n];
+
+    // Go bottom-up, so we push crates in stack order.
+    for (row_num, row) in rows.iter().enumerate().rev() {
+        let row_num = row_num + 1;
+        let row = row.as_bytes();
+
+        let num_slots = row.len().div_ceil(4);
+        ensure!(
+            num_slots <= n,
+            "row {row_num}: {num_slots} columns, but only {n} stacks"
+        );
+
+        for (j, slot) in row.chunks(4).enumerate() {
+            let (cell, sep) = slot.split_at(slot.len().min(3));
+            ensure!(
+                sep.iter().all(|&c| c == b' '),
+                "row {row_num}: expected a space after column {}",
+                j + 1
+            );
+
+            match cell {
+                [b'[', c, b']'] if c.is_ascii_uppercase() => {
+                    ensure!(
+                        stacks[j].len() == rows.len() - row_num,
+                        "row {row_num}: crate in column {} isn't resting on anything",
+                        j + 1
+                    );
+                    stacks[j].push(*c as char);
+                }
+                _ if cell.iter().all(|&c| c == b' ') => (),
+                _ => bail!(
+                    "row {row_num}: expected a crate like [X] in column {}, got {:?}",
+                    j + 1,
+                    String::from_utf8_lossy(cell)
+                ),
+            }
+        }
+    }
+
+    Ok(Stacks::new(stacks))
+}
+
+/// Return the number of stacks.
+///
+/// The footer must number the stacks 1, 2, 3, ..., with each number centered
+/// under its column.
+fn parse_footer(footer: &str) -> Result<usize> {
+    let labels = regex!(r"\d+").find_iter(footer);
+
+    let mut n = 0;
+    for (j, label) in labels.enumerate() {
+        let expected = j + 1;
+        ensure!(
+            label.as_str() == expected.to_string(),
+            "footer: expected stack number {expected}, got {}",
+            label.as_str()
+        );
+
+        let center = 4 * j + 1;
+        ensure!(
+            label.range().contains(&center),
+            "footer: stack number {expected} isn't under its column"
+        );
+
+        n = expected;
+    }
+
+    ensure!(n != 0, "footer: no stack numbers");
+    Ok(n)
+}
+
+fn parse_instruction(line: &str) -> Result<Instruction> {
+    let caps = regex!(r"^move (\d+) from (\d+) to (\d+)$")
+        .captures(line)
+        .with_context(|| format!("not an instruction: {line:?}"))?;
+    let amount = caps[1].parse()?;
+    let source: usize = caps[2].parse()?;
+    let dest: usize = caps[3].parse()?;
+
+    // 0-indexed
+    let source = source
+        .checked_sub(1)
+        .context("stacks are numbered from 1")?;
+    let dest = dest.checked_sub(1).context("stacks are numbered from 1")?;
+
+    Ok(Instruction {
+        amount,
+        source,
+        dest,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{parse_stacks, Stacks};
+
+    #[test]
+    fn drawing_matches_compact() -> Result<()> {
+        let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
+        let compact = "ZN\nMCD\nP";
+        assert_eq!(parse_stacks(drawing)?, parse_stacks(compact)?);
+        Ok(())
+    }
+
+    #[test]
+    fn ragged_drawing() -> Result<()> {
+        let drawing = "    [D]\n[N] [C]\n[Z] [M] [P]\n 1   2   3   4";
+        let expected: Stacks = ["ZN", "MCD", "P", ""].into_iter().collect();
+        assert_eq!(parse_stacks(drawing)?, expected);
+        Ok(())
+    }
+
+    #[test]
+    fn bad_drawings() {
+        // Misnumbered footer.
+        assert!(parse_stacks("[A] [B]\n 1   3").is_err());
+        // Footer doesn't line up with the columns.
+        assert!(parse_stacks("[A] [B]\n1 2").is_err());
+        // More columns than stacks.
+        assert!(parse_stacks("[A] [B] [C]\n 1   2").is_err());
+        // Floating crate.
+        assert!(parse_stacks("[A]\n    [B]\n 1   2").is_err());
+        // Garbage in a slot.
+        assert!(parse_stacks("[A] xyz\n 1   2").is_err());
+    }
+}
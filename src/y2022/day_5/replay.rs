@@ -0,0 +1,203 @@
+use std::fmt::{self, Display, Write};
+
+use anyhow::{ensure, Context, Result};
+
+use super::{Crane, Instruction, Stacks};
+
+/// Step through a list of instructions, forwards or backwards.
+///
+/// Undo relies on the crane only moving `amount` crates from `source` to
+/// `dest`, which is true of every crane in this module.
+pub struct Replay<'a, C> {
+    crane: &'a C,
+    instructions: &'a [Instruction],
+    stacks: Stacks,
+    /// For each applied instruction, the crates it lifted off `source`,
+    /// bottom to top.
+    history: Vec<Vec<char>>,
+}
+
+/// What happened when an instruction was applied or undone.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Step {
+    /// 0-indexed position in the instruction list.
+    pub index: usize,
+    pub instr: Instruction,
+    /// The crates that left `source`, bottom to top.
+    pub lifted: Vec<char>,
+    /// The same crates, as they landed on `dest`, bottom to top.
+    pub landed: Vec<char>,
+}
+
+impl<'a, C: Crane> Replay<'a, C> {
+    pub fn new(stacks: Stacks, instructions: &'a [Instruction], crane: &'a C) -> Self {
+        Self {
+            crane,
+            instructions,
+            stacks,
+            history: vec![],
+        }
+    }
+
+    /// The number of instructions applied so far.
+    pub fn position(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    /// Apply the next instruction. Return None if there are none left.
+    pub fn step(&mut self) -> Result<Option<Step>> {
+        let index = self.position();
+        let Some(&instr) = self.instructions.get(index) else {
+            return Ok(None);
+        };
+
+        self.stacks
+            .validate(instr)
+            .with_context(|| format!("instruction {}", index + 1))?;
+        let source = &self.stacks[instr.source];
+        let lifted = source[source.len() - instr.amount..].to_vec();
+
+        self.crane.execute(&mut self.stacks, instr)?;
+        let dest = &self.stacks[instr.dest];
+        let landed = dest[dest.len() - instr.amount..].to_vec();
+
+        self.history.push(lifted.clone());
+        Ok(Some(Step {
+            index,
+            instr,
+            lifted,
+            landed,
+        }))
+    }
+
+    /// Undo the last instruction. Return None if we're back at the start.
+    pub fn undo(&mut self) -> Result<Option<Step>> {
+        let Some(lifted) = self.history.pop() else {
+            return Ok(None);
+        };
+        let index = self.position();
+        let instr = self.instructions[index];
+
+        let landed = self.stacks.lift(instr.dest, instr.amount)?;
+        self.stacks.place(instr.source, &lifted)?;
+
+        Ok(Some(Step {
+            index,
+            instr,
+            lifted,
+            landed,
+        }))
+    }
+
+    /// Step forwards or backwards until exactly `k` instructions are applied.
+    pub fn seek(&mut self, k: usize) -> Result<()> {
+        let n = self.instructions.len();
+        ensure!(k <= n, "can't seek to {k}; there are only {n} instructions");
+
+        while self.position() < k {
+            self.step()?;
+        }
+        while self.position() > k {
+            self.undo()?;
+        }
+        Ok(())
+    }
+
+    /// Apply all the remaining instructions, and return one line per step
+    /// describing what moved where.
+    pub fn trace(&mut self) -> Result<String> {
+        let mut out = String::new();
+        while let Some(step) = self.step()? {
+            writeln!(out, "{step}")?;
+        }
+        Ok(out)
+    }
+}
+
+impl Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Instruction {
+            amount,
+            source,
+            dest,
+        } = self.instr;
+        let lifted: String = self.lifted.iter().collect();
+        let landed: String = self.landed.iter().collect();
+
+        write!(
+            f,
+            "{}: move {amount} from {} to {}: lifted {lifted}, landed {landed}",
+            self.index + 1,
+            source + 1,
+            dest + 1,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::Replay;
+    use crate::{
+        common::example,
+        y2022::day_5::{parse_input, parse_stacks, CrateMover9000, CrateMover9001},
+    };
+
+    #[test]
+    fn undo_restores_every_state() -> Result<()> {
+        let (stacks, instructions) = parse_input(example!(2022, 5))?;
+        let mut replay = Replay::new(stacks.clone(), &instructions, &CrateMover9000);
+
+        let mut states = vec![replay.stacks().clone()];
+        while replay.step()?.is_some() {
+            states.push(replay.stacks().clone());
+        }
+        assert_eq!(replay.stacks().message()?, "CMZ");
+
+        while replay.undo()?.is_some() {
+            assert_eq!(replay.stacks(), &states[replay.position()]);
+        }
+        assert_eq!(replay.stacks(), &stacks);
+
+        replay.seek(3)?;
+        assert_eq!(replay.stacks(), &states[3]);
+        replay.seek(1)?;
+        assert_eq!(replay.stacks(), &states[1]);
+        assert!(replay.seek(5).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn render() -> Result<()> {
+        let (stacks, instructions) = parse_input(example!(2022, 5))?;
+        let drawing = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n";
+        assert_eq!(stacks.to_string(), drawing);
+        assert_eq!(parse_stacks(&stacks.to_string())?, stacks);
+
+        let mut replay = Replay::new(stacks, &instructions, &CrateMover9001);
+        replay.seek(2)?;
+        let drawing = "        [D]\n        [N]\n    [C] [Z]\n    [M] [P]\n 1   2   3 \n";
+        assert_eq!(replay.stacks().to_string(), drawing);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace() -> Result<()> {
+        let (stacks, instructions) = parse_input(example!(2022, 5))?;
+        let mut replay = Replay::new(stacks, &instructions, &CrateMover9000);
+
+        let trace = replay.trace()?;
+        println!("{trace}");
+        let lines: Vec<_> = trace.lines().collect();
+        assert_eq!(lines[1], "2: move 3 from 1 to 3: lifted ZND, landed DNZ");
+
+        Ok(())
+    }
+}
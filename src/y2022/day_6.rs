@@ -0,0 +1,158 @@
+mod stream;
+
+use std::collections::VecDeque;
+
+use anyhow::{Context, Result};
+
+pub use self::stream::{first_markers, Event, MarkerKind, Scanner};
+
+/// The number of chars processed before the first start-of-packet marker.
+pub fn part_1(input: &str) -> Result<usize> {
+    find_magic(input, 4).context("no start-of-packet marker")
+}
+
+/// The number of chars processed before the first start-of-message marker.
+pub fn part_2(input: &str) -> Result<usize> {
+    find_magic(input, 14).context("no start-of-message marker")
+}
+
+/// Return the number of chars up to and including the magic window.
+///
+/// Panics if `window_size` is 0.
+pub fn find_magic(input: &str, window_size: usize) -> Option<usize> {
+    markers(input.as_bytes(), window_size).next()
+}
+
+/// Every magic window, given as the number of bytes up to and including it.
+///
+/// Runs in O(n) time, regardless of `window_size`. Panics if `window_size` is
+/// 0.
+pub fn markers(input: &[u8], window_size: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut window = Window::new(window_size);
+    input
+        .iter()
+        .enumerate()
+        .filter_map(move |(i, &b)| window.push(b).then_some(i + 1))
+}
+
+/// The most recent `size` bytes of a signal, along with a count of each
+/// distinct byte, so we can tell in O(1) whether they're all different.
+#[derive(Debug, Clone)]
+pub struct Window {
+    size: usize,
+    recent: VecDeque<u8>,
+    counts: [usize; 256],
+    /// The number of byte values that appear more than once.
+    repeated: usize,
+}
+
+impl Window {
+    /// Panics if `size` is 0.
+    pub fn new(size: usize) -> Self {
+        assert_ne!(size, 0, "window size must be non-zero");
+        Self {
+            size,
+            recent: VecDeque::with_capacity(size),
+            counts: [0; 256],
+            repeated: 0,
+        }
+    }
+
+    /// Slide the window forward by one byte. Return true if the window is
+    /// now full, and all its bytes are different.
+    pub fn push(&mut self, b: u8) -> bool {
+        if self.recent.len() == self.size {
+            let old = self.recent.pop_front().unwrap();
+            let count = &mut self.counts[old as usize];
+            if *count == 2 {
+                self.repeated -= 1;
+            }
+            *count -= 1;
+        }
+
+        self.recent.push_back(b);
+        let count = &mut self.counts[b as usize];
+        *count += 1;
+        if *count == 2 {
+            self.repeated += 1;
+        }
+
+        self.is_marker()
+    }
+
+    pub fn is_marker(&self) -> bool {
+        self.recent.len() == self.size && self.repeated == 0
+    }
+
+    /// The bytes currently in the window, oldest first.
+    pub fn bytes(&self) -> impl Iterator<Item = u8> + '_ {
+        self.recent.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{find_magic, markers};
+    use crate::common::{input, testing::XorShift};
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let num_chars = super::part_1(input!(2022, 6))?;
+        dbg!(num_chars);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let num_chars = super::part_2(input!(2022, 6))?;
+        dbg!(num_chars);
+        Ok(())
+    }
+
+    #[test]
+    fn examples() {
+        let cases = [
+            ("mjqjpqmgbljsphdztnvjfqwrcgsmlb", 7, 19),
+            ("bvwbjplbgvbhsrlpgdmjqwftvncz", 5, 23),
+            ("nppdvjthqldpwncqszvftbrmjlhg", 6, 23),
+            ("nznrnfrfntjfmvfwmzdfjlvtqnbhcprsg", 10, 29),
+            ("zcfzfwzzqfrljwzlrfnpqdbhtmscgvjw", 11, 26),
+        ];
+        for (s, packet, message) in cases {
+            assert_eq!(find_magic(s, 4), Some(packet));
+            assert_eq!(find_magic(s, 14), Some(message));
+        }
+    }
+
+    /// The original quadratic implementation.
+    fn all_markers_naive(input: &[u8], window_size: usize) -> Vec<usize> {
+        let all_unique = |window: &[u8]| {
+            let n = window.len();
+            (0..n).all(|i| (i + 1..n).all(|j| window[i] != window[j]))
+        };
+
+        input
+            .windows(window_size)
+            .enumerate()
+            .filter(|(_, w)| all_unique(w))
+            .map(|(i, _)| i + window_size)
+            .collect()
+    }
+
+    #[test]
+    fn matches_naive() {
+        let mut rng = XorShift::new(6);
+        for _ in 0..500 {
+            let len = rng.below(100) as usize;
+            let window_size = 1 + rng.below(20) as usize;
+            // Small alphabets make repeats likely; 256 covers all bytes.
+            let alphabet = [2, 4, 10, 26, 256][rng.below(5) as usize];
+            let input: Vec<u8> = (0..len).map(|_| rng.below(alphabet) as u8).collect();
+
+            let fast: Vec<_> = markers(&input, window_size).collect();
+            assert_eq!(fast, all_markers_naive(&input, window_size), "{input:?}");
+        }
+    }
+}
@@ -0,0 +1,195 @@
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader, ErrorKind, Read},
+};
+
+use super::Window;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerKind {
+    /// 4 distinct bytes.
+    Packet,
+    /// 14 distinct bytes.
+    Message,
+}
+
+impl MarkerKind {
+    pub fn window_size(self) -> usize {
+        match self {
+            Self::Packet => 4,
+            Self::Message => 14,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Event {
+    pub kind: MarkerKind,
+    /// The number of bytes up to and including the marker.
+    pub offset: u64,
+    pub marker: Vec<u8>,
+}
+
+/// Scan a signal for start-of-packet and start-of-message markers in a single
+/// pass, without holding more than a window's worth of it in memory.
+///
+/// Yields an event for every marker, in order of `offset`. When a byte
+/// completes both kinds of marker, the packet event comes first.
+pub struct Scanner<R> {
+    reader: R,
+    /// Bytes consumed so far.
+    offset: u64,
+    windows: [(MarkerKind, Window); 2],
+    pending: VecDeque<Event>,
+}
+
+impl<R: Read> Scanner<BufReader<R>> {
+    pub fn from_reader(reader: R) -> Self {
+        Self::new(BufReader::new(reader))
+    }
+}
+
+impl<R: BufRead> Scanner<R> {
+    pub fn new(reader: R) -> Self {
+        let windows = [MarkerKind::Packet, MarkerKind::Message]
+            .map(|kind| (kind, Window::new(kind.window_size())));
+        Self {
+            reader,
+            offset: 0,
+            windows,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// The number of bytes consumed so far.
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+}
+
+impl<R: BufRead> Iterator for Scanner<R> {
+    type Item = io::Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(Ok(event));
+            }
+
+            let chunk = match self.reader.fill_buf() {
+                Ok(chunk) => chunk,
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            if chunk.is_empty() {
+                return None;
+            }
+
+            // Only consume up to the first marker, so events come out in
+            // order without buffering the rest of the chunk.
+            let mut used = 0;
+            for &b in chunk {
+                used += 1;
+                self.offset += 1;
+
+                for (kind, window) in &mut self.windows {
+                    if window.push(b) {
+                        self.pending.push_back(Event {
+                            kind: *kind,
+                            offset: self.offset,
+                            marker: window.bytes().collect(),
+                        });
+                    }
+                }
+
+                if !self.pending.is_empty() {
+                    break;
+                }
+            }
+            self.reader.consume(used);
+        }
+    }
+}
+
+/// The offsets of the first start-of-packet and start-of-message markers.
+///
+/// Stops reading once both have been found.
+pub fn first_markers(reader: impl Read) -> io::Result<(Option<u64>, Option<u64>)> {
+    let mut packet = None;
+    let mut message = None;
+
+    for event in Scanner::from_reader(reader) {
+        let event = event?;
+        match event.kind {
+            MarkerKind::Packet => packet = packet.or(Some(event.offset)),
+            MarkerKind::Message => message = message.or(Some(event.offset)),
+        }
+        if packet.is_some() && message.is_some() {
+            break;
+        }
+    }
+
+    Ok((packet, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use anyhow::Result;
+
+    use super::{first_markers, MarkerKind, Scanner};
+    use crate::{
+        common::{input, testing::XorShift},
+        y2022::day_6::markers,
+    };
+
+    #[test]
+    fn matches_in_memory() -> Result<()> {
+        let input = input!(2022, 6).as_bytes();
+        let (packet, message) = first_markers(input)?;
+        assert_eq!(packet, markers(input, 4).next().map(|i| i as u64));
+        assert_eq!(message, markers(input, 14).next().map(|i| i as u64));
+        Ok(())
+    }
+
+    #[test]
+    fn chunk_boundaries() -> Result<()> {
+        let mut rng = XorShift::new(34);
+        let input: Vec<u8> = (0..2_000).map(|_| b'a' + rng.below(16) as u8).collect();
+
+        let offsets = |kind: MarkerKind| -> Vec<u64> {
+            markers(&input, kind.window_size())
+                .map(|i| i as u64)
+                .collect()
+        };
+        let expected_packets = offsets(MarkerKind::Packet);
+        let expected_messages = offsets(MarkerKind::Message);
+
+        for capacity in [1, 2, 3, 5, 13, 64, 4096] {
+            let reader = BufReader::with_capacity(capacity, Cursor::new(&input));
+            let events: Vec<_> = Scanner::new(reader).collect::<Result<_, _>>()?;
+
+            // Events are in stream order.
+            assert!(events.windows(2).all(|w| w[0].offset <= w[1].offset));
+
+            let of_kind = |kind| -> Vec<u64> {
+                events
+                    .iter()
+                    .filter(|e| e.kind == kind)
+                    .map(|e| e.offset)
+                    .collect()
+            };
+            assert_eq!(of_kind(MarkerKind::Packet), expected_packets);
+            assert_eq!(of_kind(MarkerKind::Message), expected_messages);
+
+            for e in &events {
+                let end = e.offset as usize;
+                let start = end - e.kind.window_size();
+                assert_eq!(e.marker, input[start..end]);
+            }
+        }
+
+        Ok(())
+    }
+}
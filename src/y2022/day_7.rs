@@ -0,0 +1,316 @@
+mod disk;
+mod json;
+mod plan;
+mod query;
+mod shell;
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Cursor},
+};
+
+use anyhow::{bail, ensure, Result};
+use lazy_regex::regex;
+
+pub use self::{
+    plan::{Objective, Plan},
+    query::{human_size, Entry, Node},
+    shell::Shell,
+};
+use crate::common::helpers::until_err;
+
+/// The sum of the sizes of all directories of size less than 100,000.
+pub fn part_1(input: &str) -> Result<usize> {
+    let commands = parse_input(BufReader::new(Cursor::new(input)))?;
+    let tree = build_tree(commands)?;
+    let sizes = tree.dir_sizes();
+
+    let mut small_sum = 0;
+    for s in sizes {
+        if s < 100_000 {
+            small_sum += s;
+        }
+    }
+
+    Ok(small_sum)
+}
+
+/// The size of the smallest directory that frees up enough space for the
+/// update, if deleted.
+pub fn part_2(input: &str) -> Result<usize> {
+    let commands = parse_input(BufReader::new(Cursor::new(input)))?;
+    let tree = build_tree(commands)?;
+    let sizes = tree.dir_sizes();
+
+    let total_disk_space = 70_000_000;
+    let space_needed_for_update = 30_000_000;
+
+    let current_usage = sizes[0];
+    let target_usage = total_disk_space - space_needed_for_update;
+
+    // Reasonableness checks.
+    ensure!(
+        current_usage < total_disk_space,
+        "we've already overflowed our disk; huh? {:}",
+        current_usage
+    );
+    ensure!(
+        current_usage > target_usage,
+        "we're already under the target usage; huh? {:}",
+        current_usage
+    );
+
+    let amount_to_reclaim = current_usage - target_usage;
+    let candidate_dirs = sizes.into_iter().filter(|&s| s >= amount_to_reclaim);
+    let answer = candidate_dirs.min().unwrap();
+
+    Ok(answer)
+}
+
+/// Assumptions:
+/// * all commands are "successful"; either:
+///   1. cd into a directory, with no console output
+///   2. ls the current directory, outputing zero or more file/dir entries
+///
+/// Each command is paired with its (1-indexed) line number.
+pub fn parse_input(input: impl BufRead) -> Result<Vec<(usize, Command)>> {
+    let mut commands = vec![];
+
+    let mut err = Ok(());
+    let mut lines = input.lines().scan(&mut err, until_err).zip(1..).peekable();
+
+    while let Some((line, line_num)) = lines.next() {
+        let command = if let Some(caps) = regex!(r"^\$ cd ([^\s]+)$").captures(&line) {
+            let dir = caps[1].to_owned();
+            Command::Cd { dir }
+        } else if regex!(r"^\$ ls$").is_match(&line) {
+            let mut dirs = vec![];
+            let mut files = vec![];
+
+            loop {
+                match lines.peek() {
+                    None => break,
+                    Some((s, _)) if s.starts_with('$') => break,
+                    _ => (),
+                }
+
+                let (line, line_num) = lines.next().unwrap();
+                if let Some(caps) = regex!(r"^dir ([^\s]+)$").captures(&line) {
+                    let name = caps[1].to_owned();
+                    dirs.push(name);
+                } else if let Some(caps) = regex!(r"^(\d+) ([^\s]+)$").captures(&line) {
+                    let size = caps[1].parse()?;
+                    let name = caps[2].to_owned();
+                    files.push(File { name, size });
+                } else {
+                    bail!(
+                        "line {line_num}: failed to match line against ls entry regexes: {line:?}"
+                    );
+                }
+            }
+
+            Command::Ls { dirs, files }
+        } else {
+            bail!("line {line_num}: failed to match line against cd/ls regexes: {line:?}");
+        };
+
+        commands.push((line_num, command));
+    }
+
+    err?;
+
+    Ok(commands)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// `dir` is a path, e.g. `a`, `..`, `/a/b`, or `a/b/../c`.
+    Cd {
+        dir: String,
+    },
+    Ls {
+        dirs: Vec<String>,
+        files: Vec<File>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    pub name: String,
+    pub size: usize,
+}
+
+/// Replay a transcript, reconstructing as much of the filesystem as it
+/// reveals. See `Shell` for the rules.
+pub fn build_tree(commands: impl IntoIterator<Item = (usize, Command)>) -> Result<Tree> {
+    let mut shell = Shell::new();
+    for (line, command) in commands {
+        shell.run(line, command)?;
+    }
+    Ok(shell.into_tree())
+}
+
+#[derive(Debug)]
+pub struct Tree {
+    /// dirs[0] is the root directory, "/"
+    ///
+    /// Because of how we build the tree, each node has a smaller index than its
+    /// children.
+    dirs: Vec<Dir>,
+}
+
+#[derive(Debug, Default)]
+pub struct Dir {
+    parent: usize,
+    /// Empty for the root.
+    name: String,
+    children: HashMap<String, usize>,
+    files: Vec<File>,
+    /// The line where `ls` was first run here, if it ever was. Line 0 means
+    /// the contents came from somewhere else, like the disk or JSON.
+    listed_on: Option<usize>,
+}
+
+impl Dir {
+    fn new(parent: usize, name: String) -> Self {
+        Self {
+            parent,
+            name,
+            ..Self::default()
+        }
+    }
+
+    /// Index of the parent directory. The root is its own parent.
+    pub fn parent(&self) -> usize {
+        self.parent
+    }
+
+    /// Empty for the root.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Child directory names, mapped to their indices.
+    pub fn children(&self) -> &HashMap<String, usize> {
+        &self.children
+    }
+
+    pub fn files(&self) -> &[File] {
+        &self.files
+    }
+
+    /// If `ls` was never run here, we only know about the subdirectories we
+    /// happened to `cd` into, and nothing about the files. So the directory is
+    /// only partially explored, and its size is a lower bound.
+    pub fn is_listed(&self) -> bool {
+        self.listed_on.is_some()
+    }
+}
+
+impl Default for Tree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Tree {
+    /// A tree with just an empty root directory, which hasn't been listed.
+    pub fn new() -> Self {
+        // The root directory is its own parent.
+        let root = Dir::new(0, String::new());
+        Self { dirs: vec![root] }
+    }
+
+    /// All directories, indexed as in `dir_sizes`. Index 0 is the root.
+    pub fn dirs(&self) -> &[Dir] {
+        &self.dirs
+    }
+
+    /// Whether every directory has been listed.
+    pub fn is_fully_explored(&self) -> bool {
+        self.dirs.iter().all(Dir::is_listed)
+    }
+
+    /// The absolute path of `name`, inside directory `i`.
+    fn join(&self, i: usize, name: &str) -> String {
+        match i {
+            0 => format!("/{name}"),
+            _ => format!("{}/{name}", self.path(i)),
+        }
+    }
+
+    /// The absolute path of directory `i`, e.g. "/a/b". The root is "/".
+    pub fn path(&self, i: usize) -> String {
+        let mut names = vec![];
+        let mut curr = i;
+        while curr != 0 {
+            names.push(self.dirs[curr].name.as_str());
+            curr = self.dirs[curr].parent;
+        }
+        names.reverse();
+        format!("/{}", names.join("/"))
+    }
+
+    /// Add an empty subdirectory, and return its index.
+    fn add_dir(&mut self, parent: usize, name: String) -> usize {
+        let idx = self.dirs.len();
+        self.dirs[parent].children.insert(name.clone(), idx);
+        self.dirs.push(Dir::new(parent, name));
+        idx
+    }
+
+    /// We can take advantage of the fact that the `dirs` list is already
+    /// topologically sorted -- that is, parents come before children.
+    ///
+    /// So we'll process the list from right-to-left, so that a node's
+    /// dependencies are processed before the node itself.
+    ///
+    /// Sizes of directories that were never listed are only lower bounds.
+    pub fn dir_sizes(&self) -> Vec<usize> {
+        let n = self.dirs.len();
+        let mut sizes = vec![0; n];
+
+        for i in (0..n).rev() {
+            let curr = &self.dirs[i];
+
+            for f in &curr.files {
+                sizes[i] += f.size;
+            }
+
+            for &child_idx in curr.children.values() {
+                assert!(i < child_idx);
+                sizes[i] += sizes[child_idx];
+            }
+        }
+
+        sizes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use crate::common::{example, input};
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let small_sum = super::part_1(input!(2022, 7))?;
+        dbg!(small_sum);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let answer = super::part_2(input!(2022, 7))?;
+        dbg!(answer);
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, 7))?, 95_437);
+        assert_eq!(super::part_2(example!(2022, 7))?, 24_933_642);
+        Ok(())
+    }
+}
@@ -0,0 +1,187 @@
+use std::{fmt::Write, fs, path::Path};
+
+use anyhow::{ensure, Context, Result};
+
+use super::{File, Tree};
+
+impl Tree {
+    /// Read a real directory, recursively, using actual file sizes.
+    ///
+    /// Symlinks are skipped, as is anything that isn't a plain file or
+    /// directory. Names must be valid UTF-8.
+    pub fn from_disk(root: impl AsRef<Path>) -> Result<Self> {
+        let mut tree = Tree::new();
+        tree.read_dir(0, root.as_ref())?;
+        Ok(tree)
+    }
+
+    /// Helper for `from_disk`. Fill in directory `dir`, which lives at `path`.
+    fn read_dir(&mut self, dir: usize, path: &Path) -> Result<()> {
+        let mut entries = fs::read_dir(path)
+            .with_context(|| format!("reading {}", path.display()))?
+            .collect::<Result<Vec<_>, _>>()?;
+        entries.sort_by_key(|e| e.file_name());
+
+        let mut subdirs = vec![];
+        for entry in entries {
+            let name = entry
+                .file_name()
+                .into_string()
+                .map_err(|name| anyhow::anyhow!("non-UTF-8 file name: {name:?}"))?;
+
+            // Unlike `DirEntry::metadata`, this is documented not to follow
+            // symlinks on every platform.
+            let meta = fs::symlink_metadata(entry.path())?;
+            let file_type = meta.file_type();
+            if file_type.is_dir() {
+                let idx = self.add_dir(dir, name);
+                subdirs.push((idx, entry.path()));
+            } else if file_type.is_file() {
+                let size = meta.len().try_into()?;
+                self.dirs[dir].files.push(File { name, size });
+            }
+        }
+        self.dirs[dir].listed_on = Some(0);
+
+        for (idx, path) in subdirs {
+            self.read_dir(idx, &path)?;
+        }
+        Ok(())
+    }
+
+    /// A `cd`/`ls` transcript that `parse_input` and `build_tree` turn back
+    /// into this tree.
+    ///
+    /// Fails if a name contains whitespace or `/`, since the transcript format
+    /// can't represent those.
+    pub fn to_transcript(&self) -> Result<String> {
+        let mut out = String::new();
+        writeln!(out, "$ cd /")?;
+        self.write_transcript(0, &mut out)?;
+        Ok(out)
+    }
+
+    /// Helper for `to_transcript`. Ends in the same directory it starts in.
+    fn write_transcript(&self, dir: usize, out: &mut String) -> Result<()> {
+        let d = &self.dirs[dir];
+
+        let mut children: Vec<_> = d.children.iter().collect();
+        children.sort_unstable();
+
+        let names = children.iter().map(|(name, _)| *name);
+        for name in names.chain(d.files.iter().map(|f| &f.name)) {
+            ensure!(
+                !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == '/'),
+                "can't write {name:?} in a transcript"
+            );
+        }
+
+        if d.is_listed() {
+            writeln!(out, "$ ls")?;
+            for (name, _) in &children {
+                writeln!(out, "dir {name}")?;
+            }
+            for f in &d.files {
+                writeln!(out, "{} {}", f.size, f.name)?;
+            }
+        }
+
+        for (name, &idx) in children {
+            writeln!(out, "$ cd {name}")?;
+            self.write_transcript(idx, out)?;
+            writeln!(out, "$ cd ..")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        io::{BufReader, Cursor},
+        path::PathBuf,
+        process,
+        time::{SystemTime, UNIX_EPOCH},
+    };
+
+    use anyhow::Result;
+
+    use crate::{
+        common::example,
+        y2022::day_7::{build_tree, parse_input, Tree},
+    };
+
+    fn from_transcript(transcript: &str) -> Result<Tree> {
+        build_tree(parse_input(BufReader::new(Cursor::new(transcript)))?)
+    }
+
+    /// A fresh, empty directory that's removed on drop.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Result<Self> {
+            let nanos = SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos();
+            let path = env::temp_dir().join(format!("advent-day-7-{}-{nanos}", process::id()));
+            fs::create_dir(&path)?;
+            Ok(Self(path))
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn disk_round_trip() -> Result<()> {
+        let tmp = TempDir::new()?;
+        let root = &tmp.0;
+
+        fs::create_dir_all(root.join("a/e"))?;
+        fs::create_dir_all(root.join("d"))?;
+        fs::create_dir_all(root.join("empty"))?;
+        for (path, size) in [
+            ("a/e/i", 584),
+            ("a/f", 29_116),
+            ("b.txt", 1_000),
+            ("d/j", 4_060),
+        ] {
+            fs::File::create(root.join(path))?.set_len(size)?;
+        }
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(root.join("a"), root.join("d/link"))?;
+            std::os::unix::fs::symlink(root.join("b.txt"), root.join("link.txt"))?;
+        }
+
+        let tree = Tree::from_disk(root)?;
+        assert!(tree.is_fully_explored());
+        assert_eq!(tree.dir_sizes()[0], 584 + 29_116 + 1_000 + 4_060);
+        assert_eq!(tree.resolve("/d/link"), None);
+        assert_eq!(tree.resolve("/link.txt"), None);
+
+        let transcript = tree.to_transcript()?;
+        let round_tripped = from_transcript(&transcript)?;
+        assert_eq!(round_tripped.render_tree(), tree.render_tree());
+
+        Ok(())
+    }
+
+    #[test]
+    fn transcript_round_trip() -> Result<()> {
+        let tree = from_transcript(example!(2022, 7))?;
+        let round_tripped = from_transcript(&tree.to_transcript()?)?;
+        assert_eq!(round_tripped.render_tree(), tree.render_tree());
+        assert_eq!(round_tripped.dir_sizes(), tree.dir_sizes());
+
+        // Unlisted directories stay unlisted.
+        let partial = from_transcript("$ cd /a/b\n$ ls\n10 x\n")?;
+        let round_tripped = from_transcript(&partial.to_transcript()?)?;
+        assert_eq!(round_tripped.render_tree(), partial.render_tree());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,259 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, ensure, Context, Result};
+
+use super::{File, Tree};
+use crate::common::json::Json;
+
+impl Tree {
+    /// The tree as nested JSON objects, one per directory:
+    ///
+    /// ```text
+    /// {"name": "/", "size": 48381165, "listed": true,
+    ///  "files": [{"name": "b.txt", "size": 14848514}, ...],
+    ///  "dirs": [{"name": "a", ...}, ...]}
+    /// ```
+    ///
+    /// `size` is the computed total, as in `dir_sizes`. Subdirectories are
+    /// sorted by name so that trees from different transcripts diff cleanly;
+    /// files keep their listing order.
+    pub fn to_json(&self) -> Json {
+        self.dir_to_json(0, &self.dir_sizes())
+    }
+
+    /// Helper for `to_json`.
+    fn dir_to_json(&self, dir: usize, sizes: &[usize]) -> Json {
+        let d = &self.dirs[dir];
+        let name = if dir == 0 { "/" } else { &d.name };
+
+        let files = d
+            .files
+            .iter()
+            .map(|f| {
+                Json::Object(vec![
+                    ("name".to_owned(), f.name.as_str().into()),
+                    ("size".to_owned(), f.size.into()),
+                ])
+            })
+            .collect();
+
+        let mut children: Vec<_> = d.children.iter().collect();
+        children.sort_unstable();
+        let dirs = children
+            .into_iter()
+            .map(|(_, &child)| self.dir_to_json(child, sizes))
+            .collect();
+
+        Json::Object(vec![
+            ("name".to_owned(), name.into()),
+            ("size".to_owned(), sizes[dir].into()),
+            ("listed".to_owned(), d.is_listed().into()),
+            ("files".to_owned(), Json::Array(files)),
+            ("dirs".to_owned(), Json::Array(dirs)),
+        ])
+    }
+
+    /// The inverse of `to_json`.
+    ///
+    /// Directories are added in pre-order, so parents still come before their
+    /// children. Every `size` has to match the total of the directory's
+    /// contents, names have to be unique within a directory, and unknown keys
+    /// are rejected. `size` and `listed` may be left out; `listed` defaults to
+    /// true.
+    pub fn from_json(json: &Json) -> Result<Self> {
+        let mut tree = Tree::new();
+        let name = field(json, "name")?.context("missing \"name\"")?.as_str()?;
+        ensure!(name == "/", "the root must be named \"/\", not {name:?}");
+        tree.dir_from_json(0, json)?;
+        Ok(tree)
+    }
+
+    /// Helper for `from_json`. Fill in directory `dir`, and return its total
+    /// size.
+    fn dir_from_json(&mut self, dir: usize, json: &Json) -> Result<usize> {
+        let path = self.path(dir);
+        let fields = json.as_object().with_context(|| path.clone())?;
+        for (key, _) in fields {
+            ensure!(
+                ["name", "size", "listed", "files", "dirs"].contains(&key.as_str()),
+                "{path}: unknown key {key:?}"
+            );
+        }
+
+        let mut names = HashSet::new();
+        let mut check_name = |name: &str| {
+            ensure!(
+                !name.is_empty() && !name.contains('/') && name != "." && name != "..",
+                "{path}: invalid name {name:?}"
+            );
+            ensure!(
+                names.insert(name.to_owned()),
+                "{path}: duplicate name {name:?}"
+            );
+            Ok(())
+        };
+
+        let mut total: usize = 0;
+
+        for file in list(json, "files").with_context(|| path.clone())? {
+            let name = field(file, "name")?.context("file is missing \"name\"")?;
+            let size = field(file, "size")?.context("file is missing \"size\"")?;
+            let name = name.as_str().with_context(|| path.clone())?;
+            let size = size
+                .as_usize()
+                .with_context(|| format!("{}: size", self.join(dir, name)))?;
+            check_name(name)?;
+            ensure!(
+                file.as_object()?.len() == 2,
+                "{}: files only have \"name\" and \"size\"",
+                self.join(dir, name)
+            );
+
+            total = total
+                .checked_add(size)
+                .with_context(|| format!("{}: total size overflows", self.join(dir, name)))?;
+            self.dirs[dir].files.push(File {
+                name: name.to_owned(),
+                size,
+            });
+        }
+
+        for child in list(json, "dirs").with_context(|| path.clone())? {
+            let name = field(child, "name")?.context("directory is missing \"name\"")?;
+            let name = name.as_str().with_context(|| path.clone())?;
+            check_name(name)?;
+
+            let idx = self.add_dir(dir, name.to_owned());
+            let size = self.dir_from_json(idx, child)?;
+            total = total
+                .checked_add(size)
+                .with_context(|| format!("{}: total size overflows", self.path(idx)))?;
+        }
+
+        let listed = match field(json, "listed")? {
+            Some(listed) => listed.as_bool().with_context(|| path.clone())?,
+            None => true,
+        };
+        if listed {
+            self.dirs[dir].listed_on = Some(0);
+        } else {
+            ensure!(
+                self.dirs[dir].files.is_empty(),
+                "{path}: unlisted directories can't have files"
+            );
+        }
+
+        if let Some(size) = field(json, "size")? {
+            let size = size.as_usize().with_context(|| format!("{path}: size"))?;
+            ensure!(
+                size == total,
+                "{path}: size is {size}, but the contents add up to {total}"
+            );
+        }
+
+        Ok(total)
+    }
+}
+
+/// Helper for `from_json`. The value of `key`, if present.
+fn field<'a>(json: &'a Json, key: &str) -> Result<Option<&'a Json>> {
+    let fields = json.as_object()?;
+    let mut matches = fields.iter().filter(|(k, _)| k == key);
+    let value = matches.next().map(|(_, v)| v);
+    if matches.next().is_some() {
+        bail!("duplicate key {key:?}");
+    }
+    Ok(value)
+}
+
+/// Helper for `from_json`. The array under `key`, or an empty one if absent.
+fn list<'a>(json: &'a Json, key: &str) -> Result<&'a [Json]> {
+    match field(json, key)? {
+        Some(value) => value.as_array().with_context(|| format!("{key:?}")),
+        None => Ok(&[]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use anyhow::Result;
+
+    use crate::{
+        common::{example, input, json::Json},
+        y2022::day_7::{build_tree, parse_input, Tree},
+    };
+
+    fn from_transcript(transcript: &str) -> Result<Tree> {
+        build_tree(parse_input(BufReader::new(Cursor::new(transcript)))?)
+    }
+
+    fn round_trip(tree: &Tree) -> Result<Tree> {
+        let text = tree.to_json().pretty();
+        let round_tripped = Tree::from_json(&Json::parse(&text)?)?;
+
+        for (i, dir) in round_tripped.dirs().iter().enumerate().skip(1) {
+            assert!(dir.parent() < i);
+        }
+        assert_eq!(round_tripped.to_json().pretty(), text);
+        assert_eq!(round_tripped.render_tree(), tree.render_tree());
+        Ok(round_tripped)
+    }
+
+    #[test]
+    fn json_round_trip() -> Result<()> {
+        let tree = from_transcript(example!(2022, 7))?;
+        let round_tripped = round_trip(&tree)?;
+        assert_eq!(round_tripped.dir_sizes()[0], 48_381_165);
+
+        let text = from_transcript("$ cd /\n$ ls\ndir a\n10 x\n")?
+            .to_json()
+            .to_string();
+        assert_eq!(
+            text,
+            concat!(
+                r#"{"name":"/","size":10,"listed":true,"#,
+                r#""files":[{"name":"x","size":10}],"#,
+                r#""dirs":[{"name":"a","size":0,"listed":false,"files":[],"dirs":[]}]}"#,
+            )
+        );
+
+        // Unlisted directories stay unlisted.
+        round_trip(&from_transcript("$ cd /a/b\n$ ls\n10 x\n")?)?;
+        round_trip(&from_transcript(input!(2022, 7))?)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_json() {
+        let bad = [
+            // Not a tree at all.
+            r#"[]"#,
+            r#"{"name": "a"}"#,
+            // Wrong total.
+            r#"{"name": "/", "size": 11, "files": [{"name": "x", "size": 10}]}"#,
+            // Bad names.
+            r#"{"name": "/", "files": [{"name": "x", "size": 1}], "dirs": [{"name": "x"}]}"#,
+            r#"{"name": "/", "dirs": [{"name": "a/b"}]}"#,
+            r#"{"name": "/", "dirs": [{"name": ".."}]}"#,
+            // Bad sizes.
+            r#"{"name": "/", "files": [{"name": "x", "size": -1}]}"#,
+            r#"{"name": "/", "files": [{"name": "x", "size": 1.5}]}"#,
+            r#"{"name": "/", "files": [{"name": "x", "size": 18446744073709551615}, {"name": "y", "size": 1}]}"#,
+            r#"{"name": "/", "files": [{"name": "x", "size": 18446744073709551615}], "dirs": [{"name": "a", "files": [{"name": "y", "size": 1}]}]}"#,
+            // Unknown or duplicate keys.
+            r#"{"name": "/", "owner": "elf"}"#,
+            r#"{"name": "/", "files": [{"name": "x", "size": 1, "mode": 644}]}"#,
+            r#"{"name": "/", "name": "/"}"#,
+            // Files in a directory that was never listed.
+            r#"{"name": "/", "listed": false, "files": [{"name": "x", "size": 1}]}"#,
+        ];
+
+        for json in bad {
+            let json = Json::parse(json).unwrap();
+            assert!(Tree::from_json(&json).is_err(), "{json}");
+        }
+    }
+}
@@ -0,0 +1,524 @@
+use std::cmp::Reverse;
+
+use anyhow::{ensure, Result};
+
+use super::{Entry, Node, Tree};
+
+/// What to minimize when choosing what to delete.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Objective {
+    /// Delete as few bytes as possible. The chosen files are then merged into
+    /// whole directories wherever that doesn't change the total.
+    LeastBytes,
+    /// Delete as few files and directories as possible, and then as few bytes
+    /// as possible.
+    FewestDeletions,
+}
+
+/// A set of files and directories to delete, none inside another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    /// In tree order, as in `Tree::descendants`.
+    pub deletions: Vec<Entry>,
+    pub freed: usize,
+}
+
+impl Tree {
+    /// Choose what to delete so that at least `required_free` bytes are free
+    /// on a disk of size `disk_size`. The root itself can't be deleted.
+    ///
+    /// This is a knapsack problem over the tree, with the constraint that we
+    /// can't delete both a directory and something inside it.
+    pub fn plan_deletion(
+        &self,
+        disk_size: usize,
+        required_free: usize,
+        objective: Objective,
+    ) -> Result<Plan> {
+        let usage = self.dir_sizes()[0];
+        ensure!(
+            usage <= disk_size,
+            "we've already overflowed our disk: {usage} > {disk_size}"
+        );
+        ensure!(
+            required_free <= disk_size,
+            "can't free {required_free} on a disk of size {disk_size}"
+        );
+
+        let need = required_free.saturating_sub(disk_size - usage);
+        let items = Items::new(self.descendants(0));
+
+        let chosen = match objective {
+            _ if need == 0 => vec![],
+            Objective::LeastBytes => items.least_bytes(need)?,
+            Objective::FewestDeletions => items.fewest_deletions(need)?,
+        };
+
+        let deletions: Vec<_> = chosen
+            .into_iter()
+            .map(|i| items.entries[i].clone())
+            .collect();
+        let freed = deletions.iter().map(|e| e.size).sum();
+        Ok(Plan { deletions, freed })
+    }
+}
+
+/// Everything that could be deleted, in pre-order.
+///
+/// Deleting `entries[i]` means skipping ahead to `ends[i]`, since everything
+/// in between is inside it.
+struct Items {
+    entries: Vec<Entry>,
+    ends: Vec<usize>,
+}
+
+impl Items {
+    fn new(entries: Vec<Entry>) -> Self {
+        let n = entries.len();
+        let mut ends: Vec<_> = (1..=n).collect();
+
+        // Directories that we're still inside of.
+        let mut open: Vec<(usize, String)> = vec![];
+        for (i, e) in entries.iter().enumerate() {
+            while let Some((j, _)) = open.pop_if(|(_, prefix)| !e.path.starts_with(&*prefix)) {
+                ends[j] = i;
+            }
+            if let Node::Dir(_) = e.node {
+                open.push((i, format!("{}/", e.path)));
+            }
+        }
+        for (j, _) in open {
+            ends[j] = n;
+        }
+
+        Self { entries, ends }
+    }
+
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Subset-sum over the files, then merge them into directories.
+    ///
+    /// We keep every distinct total below `need` that some of the files so far
+    /// add up to, and the smallest one at or above it. Totals that can't reach
+    /// `need` even with all the remaining files are dropped. So memory depends
+    /// on how many totals there are, not how big they are, and we give up
+    /// rather than keep more than `MAX_TOTALS`.
+    fn least_bytes(&self, need: usize) -> Result<Vec<usize>> {
+        let mut files: Vec<_> = (0..self.len())
+            .filter(|&i| matches!(self.entries[i].node, Node::File { .. }))
+            .filter(|&i| self.entries[i].size > 0)
+            .collect();
+        ensure!(!files.is_empty(), "nothing to delete");
+        files.sort_by_key(|&i| Reverse(self.entries[i].size));
+
+        let sizes: Vec<_> = files.iter().map(|&i| self.entries[i].size).collect();
+        let mut rest = vec![0; sizes.len() + 1];
+        for k in (0..sizes.len()).rev() {
+            rest[k] = rest[k + 1] + sizes[k];
+        }
+        ensure!(
+            rest[0] >= need,
+            "can't free {need} bytes, even by deleting everything"
+        );
+        let target = target(need, &sizes);
+
+        // Each total is (sum, the file that reached it, the total before).
+        let mut totals = vec![(0, usize::MAX, usize::MAX)];
+        // Indices into `totals`, in increasing order of sum, all below `need`.
+        let mut open = vec![0];
+        let mut best: Option<usize> = None;
+
+        for (k, &size) in sizes.iter().enumerate() {
+            let mut reached = Vec::with_capacity(open.len());
+            for &t in &open {
+                let sum = totals[t].0 + size;
+                if sum < need {
+                    reached.push((sum, t));
+                } else if best.is_none_or(|b| sum < totals[b].0) {
+                    totals.push((sum, k, t));
+                    best = Some(totals.len() - 1);
+                }
+            }
+            if best.is_some_and(|b| totals[b].0 == target) {
+                break;
+            }
+
+            // Merge the new totals in, skipping ones we already had, and
+            // dropping any that can't get to `need` any more.
+            let mut merged = Vec::with_capacity(open.len() + reached.len());
+            let mut reached = reached.into_iter().peekable();
+            for &t in &open {
+                let sum = totals[t].0;
+                while let Some((new, prev)) = reached.next_if(|&(new, _)| new <= sum) {
+                    if new < sum {
+                        totals.push((new, k, prev));
+                        merged.push(totals.len() - 1);
+                    }
+                }
+                merged.push(t);
+            }
+            for (new, prev) in reached {
+                totals.push((new, k, prev));
+                merged.push(totals.len() - 1);
+            }
+            merged.retain(|&t| totals[t].0 + rest[k + 1] >= need);
+            open = merged;
+
+            ensure!(
+                totals.len() <= MAX_TOTALS,
+                "gave up looking for the least bytes to free after {MAX_TOTALS} totals"
+            );
+        }
+
+        let mut selected = vec![false; self.len()];
+        let mut t = best.expect("deleting everything is enough");
+        while t != 0 {
+            let (_, k, prev) = totals[t];
+            selected[files[k]] = true;
+            t = prev;
+        }
+        Ok(self.merge_into_dirs(&selected))
+    }
+
+    /// Replace each directory whose (non-empty) files are all selected with
+    /// the directory itself.
+    fn merge_into_dirs(&self, selected: &[bool]) -> Vec<usize> {
+        // Prefix sums, so we can count files in any range of entries.
+        let mut files = vec![0];
+        let mut picked = vec![0];
+        for (i, e) in self.entries.iter().enumerate() {
+            let is_file = matches!(e.node, Node::File { .. }) && e.size > 0;
+            files.push(files[i] + is_file as usize);
+            picked.push(picked[i] + selected[i] as usize);
+        }
+
+        let mut chosen = vec![];
+        let mut i = 0;
+        while i < self.len() {
+            let end = self.ends[i];
+            let num_files = files[end] - files[i];
+            let num_picked = picked[end] - picked[i];
+
+            let whole_dir = matches!(self.entries[i].node, Node::Dir(_))
+                && num_files != 0
+                && num_files == num_picked;
+            if whole_dir || selected[i] {
+                chosen.push(i);
+                i = end;
+            } else {
+                i += 1;
+            }
+        }
+        chosen
+    }
+
+    /// Find the fewest deletions that suffice, then search for the ones that
+    /// free the least bytes. Gives up, rather than running forever, after
+    /// `MAX_STEPS`.
+    fn fewest_deletions(&self, need: usize) -> Result<Vec<usize>> {
+        let n = self.len();
+
+        // most[i] is the most bytes we can free from entries i.. with at most
+        // r deletions, for r = 0, 1, ... until it's enough. We only keep the
+        // last two rows.
+        let mut most = vec![0; n + 1];
+        let mut r = 0;
+        while most[0] < need {
+            r += 1;
+            ensure!(
+                r <= n,
+                "can't free {need} bytes, even by deleting everything"
+            );
+
+            let mut next = vec![0; n + 1];
+            for i in (0..n).rev() {
+                let take = self.entries[i].size + most[self.ends[i]];
+                next[i] = next[i + 1].max(take);
+            }
+            most = next;
+        }
+
+        // largest[i] is the biggest single entry in i.., so with `r` deletions
+        // left we can't free more than `r` times that.
+        let mut largest = vec![0; n + 1];
+        for i in (0..n).rev() {
+            largest[i] = largest[i + 1].max(self.entries[i].size);
+        }
+
+        let sizes: Vec<_> = self.entries.iter().map(|e| e.size).collect();
+        let mut search = Search {
+            items: self,
+            most: &most,
+            largest: &largest,
+            need,
+            target: target(need, &sizes),
+            chosen: vec![],
+            best: None,
+            steps: 0,
+        };
+        search.run(0, r, 0)?;
+
+        let (_, chosen) = search.best.expect("`most` says it's possible");
+        Ok(chosen)
+    }
+}
+
+/// A lower bound on the total of any choice of `sizes` that frees at least
+/// `need`: every total is a multiple of their GCD. Once we find this, we can
+/// stop looking.
+fn target(need: usize, sizes: &[usize]) -> usize {
+    let gcd = sizes.iter().fold(0, |a, &b| gcd(a, b)).max(1);
+    need.div_ceil(gcd) * gcd
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// How many calls `Search::run` can make before it gives up.
+const MAX_STEPS: usize = 50_000_000;
+
+/// How many totals `Items::least_bytes` can keep track of before it gives up.
+const MAX_TOTALS: usize = 1 << 22;
+
+/// Branch and bound, for `Items::fewest_deletions`.
+struct Search<'a> {
+    items: &'a Items,
+    /// The most bytes entries i.. can free with all the deletions we have.
+    most: &'a [usize],
+    /// The biggest single entry in i...
+    largest: &'a [usize],
+    need: usize,
+    /// See `target`.
+    target: usize,
+    chosen: Vec<usize>,
+    /// The smallest total found so far, and what achieves it.
+    best: Option<(usize, Vec<usize>)>,
+    steps: usize,
+}
+
+impl Search<'_> {
+    /// Decide about entries i.., with at most `r` more deletions.
+    fn run(&mut self, i: usize, r: usize, total: usize) -> Result<()> {
+        if total >= self.need {
+            if self.best.as_ref().is_none_or(|(b, _)| total < *b) {
+                self.best = Some((total, self.chosen.clone()));
+            }
+            return Ok(());
+        }
+
+        // Can we still get there, and do better?
+        let exact = self.best.as_ref().is_some_and(|(b, _)| *b == self.target);
+        let most = self.most[i].min(r.saturating_mul(self.largest[i]));
+        if exact || r == 0 || i == self.items.len() || total + most < self.need {
+            return Ok(());
+        }
+
+        self.steps += 1;
+        ensure!(
+            self.steps <= MAX_STEPS,
+            "gave up looking for the fewest deletions after {MAX_STEPS} steps"
+        );
+
+        let size = self.items.entries[i].size;
+        let improves = self.best.as_ref().is_none_or(|(b, _)| total + size < *b);
+        if size > 0 && improves {
+            self.chosen.push(i);
+            self.run(self.items.ends[i], r - 1, total + size)?;
+            self.chosen.pop();
+        }
+
+        self.run(i + 1, r, total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use anyhow::Result;
+
+    use super::{Objective, Plan};
+    use crate::{
+        common::{example, input, testing::XorShift},
+        y2022::day_7::{build_tree, parse_input, part_2, Tree},
+    };
+
+    fn tree(transcript: &str) -> Result<Tree> {
+        build_tree(parse_input(BufReader::new(Cursor::new(transcript)))?)
+    }
+
+    fn paths(plan: &Plan) -> Vec<&str> {
+        plan.deletions.iter().map(|e| e.path.as_str()).collect()
+    }
+
+    const SMALL: &str = "\
+$ ls
+dir a
+dir b
+$ cd a
+$ ls
+60 x
+50 y
+$ cd ../b
+$ ls
+45 z
+10 w
+";
+
+    #[test]
+    fn objectives_differ() -> Result<()> {
+        let tree = tree(SMALL)?;
+
+        // 165 used, so 35 free; we need 65 more.
+        let plan = tree.plan_deletion(200, 100, Objective::FewestDeletions)?;
+        assert_eq!(paths(&plan), ["/a"]);
+        assert_eq!(plan.freed, 110);
+
+        let plan = tree.plan_deletion(200, 100, Objective::LeastBytes)?;
+        assert_eq!(paths(&plan), ["/a/x", "/b/w"]);
+        assert_eq!(plan.freed, 70);
+
+        Ok(())
+    }
+
+    #[test]
+    fn merges_whole_dirs() -> Result<()> {
+        let tree = tree(SMALL)?;
+
+        // We need 55 more, which is exactly /b.
+        let plan = tree.plan_deletion(200, 90, Objective::LeastBytes)?;
+        assert_eq!(paths(&plan), ["/b"]);
+        assert_eq!(plan.freed, 55);
+
+        Ok(())
+    }
+
+    #[test]
+    fn edge_cases() -> Result<()> {
+        let tree = tree(SMALL)?;
+
+        let plan = tree.plan_deletion(200, 35, Objective::LeastBytes)?;
+        assert!(plan.deletions.is_empty());
+
+        assert!(tree.plan_deletion(200, 201, Objective::LeastBytes).is_err());
+        assert!(tree.plan_deletion(100, 10, Objective::LeastBytes).is_err());
+
+        // Freeing everything is fine, but the root itself stays.
+        for objective in [Objective::LeastBytes, Objective::FewestDeletions] {
+            let plan = tree.plan_deletion(200, 200, objective)?;
+            assert_eq!(paths(&plan), ["/a", "/b"]);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn huge_files() -> Result<()> {
+        // Memory doesn't depend on the sizes, only on how many files there are.
+        let gb = 1 << 30;
+        let transcript = format!(
+            "$ ls\n{} a\n{} b\n{} c\n{} d\n",
+            7 * gb,
+            5 * gb,
+            4 * gb,
+            3 * gb + 1
+        );
+        let tree = tree(&transcript)?;
+        let plan = tree.plan_deletion(20 * gb, 12 * gb, Objective::LeastBytes)?;
+        // We need 11 GB and a byte, and b, c and d would be a byte more.
+        assert_eq!(paths(&plan), ["/a", "/b"]);
+        assert_eq!(plan.freed, 12 * gb);
+        Ok(())
+    }
+
+    #[test]
+    fn least_bytes_is_optimal() -> Result<()> {
+        let mut rng = XorShift::new(7);
+        for _ in 0..200 {
+            let sizes: Vec<_> = (0..1 + rng.below(10))
+                .map(|_| 1 + rng.below(50) as usize)
+                .collect();
+            let transcript: String = sizes
+                .iter()
+                .enumerate()
+                .map(|(i, size)| format!("{size} f{i}\n"))
+                .collect();
+            let tree = tree(&format!("$ ls\n{transcript}"))?;
+
+            let used: usize = sizes.iter().sum();
+            let need = rng.below(used as u64 + 1) as usize;
+            let plan = tree.plan_deletion(used, need, Objective::LeastBytes)?;
+
+            let best = (0..1 << sizes.len())
+                .map(|mask: u32| {
+                    let bits = 0..sizes.len();
+                    bits.filter(|k| mask & (1 << k) != 0)
+                        .map(|k| sizes[k])
+                        .sum()
+                })
+                .filter(|&total: &usize| total >= need)
+                .min();
+            let expected = if need == 0 { Some(0) } else { best };
+            assert_eq!(Some(plan.freed), expected, "{sizes:?}, need {need}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        let tree = tree(example!(2022, 7))?;
+        for objective in [Objective::LeastBytes, Objective::FewestDeletions] {
+            let plan = tree.plan_deletion(70_000_000, 30_000_000, objective)?;
+            assert_eq!(paths(&plan), ["/c.dat"]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn real_input() -> Result<()> {
+        let tree = tree(input!(2022, 7))?;
+        let smallest_dir = part_2(input!(2022, 7))?;
+
+        let fewest = tree.plan_deletion(70_000_000, 30_000_000, Objective::FewestDeletions)?;
+        assert_eq!(fewest.deletions.len(), 1);
+        assert!(fewest.freed <= smallest_dir);
+
+        let least = tree.plan_deletion(70_000_000, 30_000_000, Objective::LeastBytes)?;
+        assert!(least.freed <= fewest.freed);
+
+        Ok(())
+    }
+
+    #[test]
+    fn hard_searches() -> Result<()> {
+        // Many files of similar sizes, and half of them to delete. With only
+        // even sizes, an odd `need` can't be hit exactly, so the searches have
+        // to know when they've found the best they can.
+        let mut rng = XorShift::new(40);
+        for even in [false, true] {
+            let transcript: String = (0..40)
+                .map(|i| {
+                    let size = 1000 + rng.below(97);
+                    let size = if even { size & !1 } else { size };
+                    format!("{size} f{i}\n")
+                })
+                .collect();
+            let tree = tree(&format!("$ ls\n{transcript}"))?;
+            let used = tree.dir_sizes()[0];
+            let need = used / 2 + 1;
+
+            let fewest = tree.plan_deletion(used, need, Objective::FewestDeletions)?;
+            let least = tree.plan_deletion(used, need, Objective::LeastBytes)?;
+            assert!(least.freed <= fewest.freed);
+            assert!(fewest.freed >= need);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,332 @@
+use std::fmt::Write;
+
+use super::{File, Tree};
+use crate::common::groups::top_k;
+
+/// Something in the filesystem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    /// Indexed as in `Tree::dirs`.
+    Dir(usize),
+    /// The `file`th file in directory `dir`.
+    File { dir: usize, file: usize },
+}
+
+/// A node, along with its absolute path and total size.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: String,
+    pub node: Node,
+    pub size: usize,
+}
+
+impl Tree {
+    /// Look up a path, like `/a/e/i`. Relative paths start at the root, and
+    /// `.` and `..` components are allowed.
+    pub fn resolve(&self, path: &str) -> Option<Node> {
+        let mut curr = 0;
+        let mut names = path.split('/').peekable();
+
+        while let Some(name) = names.next() {
+            match name {
+                "" | "." => (),
+                ".." => curr = self.dirs[curr].parent,
+                name => {
+                    let dir = &self.dirs[curr];
+                    if let Some(&idx) = dir.children.get(name) {
+                        curr = idx;
+                    } else {
+                        // Only the last component can be a file.
+                        let file = dir.files.iter().position(|f| f.name == name)?;
+                        return names
+                            .all(|n| n.is_empty())
+                            .then_some(Node::File { dir: curr, file });
+                    }
+                }
+            }
+        }
+
+        Some(Node::Dir(curr))
+    }
+
+    pub fn file(&self, dir: usize, file: usize) -> &File {
+        &self.dirs[dir].files[file]
+    }
+
+    /// Everything inside directory `dir`, depth-first, sorted by name within
+    /// each directory. Each directory comes before its contents.
+    pub fn descendants(&self, dir: usize) -> Vec<Entry> {
+        let sizes = self.dir_sizes();
+        let mut out = vec![];
+        self.descend(dir, &sizes, &mut out);
+        out
+    }
+
+    /// Helper for `descendants`.
+    fn descend(&self, dir: usize, sizes: &[usize], out: &mut Vec<Entry>) {
+        for (name, node) in self.sorted_contents(dir) {
+            let path = self.join(dir, name);
+            match node {
+                Node::Dir(idx) => {
+                    out.push(Entry {
+                        path,
+                        node,
+                        size: sizes[idx],
+                    });
+                    self.descend(idx, sizes, out);
+                }
+                Node::File { dir, file } => out.push(Entry {
+                    path,
+                    node,
+                    size: self.file(dir, file).size,
+                }),
+            }
+        }
+    }
+
+    /// The subdirectories and files directly inside `dir`, sorted by name.
+    fn sorted_contents(&self, dir: usize) -> Vec<(&str, Node)> {
+        let d = &self.dirs[dir];
+        let dirs = d
+            .children
+            .iter()
+            .map(|(name, &idx)| (name.as_str(), Node::Dir(idx)));
+        let files = d
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (f.name.as_str(), Node::File { dir, file: i }));
+
+        let mut contents: Vec<_> = dirs.chain(files).collect();
+        contents.sort_unstable_by_key(|&(name, _)| name);
+        contents
+    }
+
+    /// The root, followed by all its descendants.
+    pub fn entries(&self) -> Vec<Entry> {
+        let root = Entry {
+            path: "/".to_owned(),
+            node: Node::Dir(0),
+            size: self.dir_sizes()[0],
+        };
+        let mut entries = vec![root];
+        entries.extend(self.descendants(0));
+        entries
+    }
+
+    /// All directories (including the root) whose total size matches.
+    pub fn find_dirs(&self, mut pred: impl FnMut(usize) -> bool) -> Vec<Entry> {
+        self.entries()
+            .into_iter()
+            .filter(|e| matches!(e.node, Node::Dir(_)) && pred(e.size))
+            .collect()
+    }
+
+    /// All files whose size matches.
+    pub fn find_files(&self, mut pred: impl FnMut(usize) -> bool) -> Vec<Entry> {
+        self.descendants(0)
+            .into_iter()
+            .filter(|e| matches!(e.node, Node::File { .. }) && pred(e.size))
+            .collect()
+    }
+
+    /// The `n` largest files, largest first.
+    pub fn largest_files(&self, n: usize) -> Vec<Entry> {
+        top_k(self.find_files(|_| true), n, |e| e.size)
+    }
+
+    /// Like `du -h`: one line per directory with its total size, each
+    /// directory after its contents, and the root last.
+    pub fn du(&self) -> String {
+        let sizes = self.dir_sizes();
+        let mut out = String::new();
+        self.du_dir(0, &sizes, &mut out);
+        out
+    }
+
+    /// Helper for `du`.
+    fn du_dir(&self, dir: usize, sizes: &[usize], out: &mut String) {
+        for (_, node) in self.sorted_contents(dir) {
+            if let Node::Dir(idx) = node {
+                self.du_dir(idx, sizes, out);
+            }
+        }
+        writeln!(out, "{}\t{}", human_size(sizes[dir]), self.path(dir)).unwrap();
+    }
+
+    /// Like `tree`: the whole hierarchy, with total sizes in parentheses.
+    ///
+    /// Directories get a trailing `/`, and those that were never listed are
+    /// flagged, since their contents may be incomplete.
+    pub fn render_tree(&self) -> String {
+        let sizes = self.dir_sizes();
+        let mut out = String::new();
+        writeln!(out, "/ ({}){}", sizes[0], self.unlisted_flag(0)).unwrap();
+        self.render_subtree(0, "", &sizes, &mut out);
+        out
+    }
+
+    /// Helper for `render_tree`.
+    fn render_subtree(&self, dir: usize, prefix: &str, sizes: &[usize], out: &mut String) {
+        let contents = self.sorted_contents(dir);
+        let n = contents.len();
+
+        for (i, (name, node)) in contents.into_iter().enumerate() {
+            let last = i + 1 == n;
+            let branch = if last { "└── " } else { "├── " };
+
+            match node {
+                Node::Dir(idx) => {
+                    let flag = self.unlisted_flag(idx);
+                    writeln!(out, "{prefix}{branch}{name}/ ({}){flag}", sizes[idx]).unwrap();
+
+                    let indent = if last { "    " } else { "│   " };
+                    self.render_subtree(idx, &format!("{prefix}{indent}"), sizes, out);
+                }
+                Node::File { dir, file } => {
+                    let size = self.file(dir, file).size;
+                    writeln!(out, "{prefix}{branch}{name} ({size})").unwrap();
+                }
+            }
+        }
+    }
+
+    fn unlisted_flag(&self, dir: usize) -> &'static str {
+        if self.dirs[dir].is_listed() {
+            ""
+        } else {
+            " [not listed]"
+        }
+    }
+}
+
+/// Like `du -h`: powers of 1024, rounded up, with one decimal place below 10.
+pub fn human_size(bytes: usize) -> String {
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < 4 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    let suffixes = ["", "K", "M", "G", "T"];
+    if unit == 0 {
+        return format!("{bytes}");
+    }
+
+    // Round before picking the format, since rounding can carry into the next
+    // digit, or the next unit.
+    let tenths = (size * 10.0).ceil() / 10.0;
+    let whole = size.ceil();
+    if tenths < 10.0 {
+        format!("{tenths:.1}{}", suffixes[unit])
+    } else if whole >= 1024.0 && unit < 4 {
+        format!("1.0{}", suffixes[unit + 1])
+    } else {
+        format!("{whole:.0}{}", suffixes[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{BufReader, Cursor};
+
+    use anyhow::Result;
+
+    use super::{human_size, Node};
+    use crate::{
+        common::example,
+        y2022::day_7::{build_tree, parse_input, Tree},
+    };
+
+    fn example_tree() -> Result<Tree> {
+        let commands = parse_input(BufReader::new(Cursor::new(example!(2022, 7))))?;
+        build_tree(commands)
+    }
+
+    fn paths(entries: Vec<super::Entry>) -> Vec<String> {
+        entries.into_iter().map(|e| e.path).collect()
+    }
+
+    #[test]
+    fn resolve() -> Result<()> {
+        let tree = example_tree()?;
+
+        assert_eq!(tree.resolve("/"), Some(Node::Dir(0)));
+        let Some(Node::Dir(e)) = tree.resolve("/a/e") else {
+            panic!("expected a dir");
+        };
+        assert_eq!(tree.path(e), "/a/e");
+
+        let Some(Node::File { dir, file }) = tree.resolve("/a/../d/./j") else {
+            panic!("expected a file");
+        };
+        assert_eq!(tree.file(dir, file).size, 4_060_174);
+
+        assert_eq!(tree.resolve("/nope"), None);
+        assert_eq!(tree.resolve("/b.txt/x"), None);
+        Ok(())
+    }
+
+    #[test]
+    fn queries() -> Result<()> {
+        let tree = example_tree()?;
+
+        let small = tree.find_dirs(|s| s <= 100_000);
+        assert_eq!(paths(small), ["/a", "/a/e"]);
+
+        let big = tree.largest_files(2);
+        assert_eq!(paths(big), ["/b.txt", "/c.dat"]);
+
+        let under_a = tree.descendants(1);
+        assert_eq!(
+            paths(under_a),
+            ["/a/e", "/a/e/i", "/a/f", "/a/g", "/a/h.lst"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn reports() -> Result<()> {
+        let tree = example_tree()?;
+
+        let du = "\
+584\t/a/e
+93K\t/a
+24M\t/d
+47M\t/
+";
+        assert_eq!(tree.du(), du);
+
+        let rendered = "\
+/ (48381165)
+├── a/ (94853)
+│   ├── e/ (584)
+│   │   └── i (584)
+│   ├── f (29116)
+│   ├── g (2557)
+│   └── h.lst (62596)
+├── b.txt (14848514)
+├── c.dat (8504156)
+└── d/ (24933642)
+    ├── d.ext (5626152)
+    ├── d.log (8033020)
+    ├── j (4060174)
+    └── k (7214296)
+";
+        assert_eq!(tree.render_tree(), rendered);
+        Ok(())
+    }
+
+    #[test]
+    fn human_sizes() {
+        assert_eq!(human_size(0), "0");
+        assert_eq!(human_size(1023), "1023");
+        assert_eq!(human_size(1024), "1.0K");
+        assert_eq!(human_size(1025), "1.1K");
+        assert_eq!(human_size(10 * 1024), "10K");
+        assert_eq!(human_size(10 * 1024 - 1), "10K");
+        assert_eq!(human_size((1 << 20) - 1), "1.0M");
+        assert_eq!(human_size(5 << 30), "5.0G");
+    }
+}
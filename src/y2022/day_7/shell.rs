@@ -0,0 +1,250 @@
+use std::collections::HashSet;
+
+use anyhow::{bail, ensure, Result};
+
+use super::{Command, File, Tree};
+
+/// Emulates a shell session, to rebuild the filesystem from its transcript.
+///
+/// Any transcript that a real shell could have produced is accepted:
+/// * `ls` may be run any number of times per directory, including zero
+/// * `cd` may enter a directory before it's been listed, and takes absolute
+///   or relative paths with any number of components, e.g. `/a/b` or
+///   `a/b/../c`
+///
+/// The filesystem is assumed not to change during the session. So if two
+/// listings of the same directory disagree, or a `cd` contradicts a listing,
+/// that's an error.
+#[derive(Debug)]
+pub struct Shell {
+    tree: Tree,
+    /// The current directory.
+    cwd: usize,
+}
+
+impl Default for Shell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shell {
+    /// Start at the root of an unexplored filesystem.
+    pub fn new() -> Self {
+        Self {
+            tree: Tree::new(),
+            cwd: 0,
+        }
+    }
+
+    /// The index of the current directory.
+    pub fn cwd(&self) -> usize {
+        self.cwd
+    }
+
+    pub fn tree(&self) -> &Tree {
+        &self.tree
+    }
+
+    pub fn into_tree(self) -> Tree {
+        self.tree
+    }
+
+    /// Run one command, found on the given line of the transcript.
+    pub fn run(&mut self, line: usize, command: Command) -> Result<()> {
+        match command {
+            Command::Cd { dir } => self.cd(line, &dir),
+            Command::Ls { dirs, files } => self.ls(line, dirs, files),
+        }
+    }
+
+    fn cd(&mut self, line: usize, path: &str) -> Result<()> {
+        let mut curr = if path.starts_with('/') { 0 } else { self.cwd };
+
+        for name in path.split('/') {
+            match name {
+                "" | "." => (),
+                ".." => curr = self.tree.dirs[curr].parent,
+                name => curr = self.subdir(line, curr, name)?,
+            }
+        }
+
+        self.cwd = curr;
+        Ok(())
+    }
+
+    /// Find the named subdirectory, creating it if we haven't seen it yet.
+    fn subdir(&mut self, line: usize, parent: usize, name: &str) -> Result<usize> {
+        let dir = &self.tree.dirs[parent];
+        if let Some(&idx) = dir.children.get(name) {
+            return Ok(idx);
+        }
+
+        // If we've already listed the parent, we know everything in it.
+        if let Some(prev) = dir.listed_on {
+            let problem = if dir.files.iter().any(|f| f.name == name) {
+                "is a file"
+            } else {
+                "doesn't exist"
+            };
+            let path = self.tree.join(parent, name);
+            bail!("line {line}: can't cd into {path}: it {problem}, according to the listing on line {prev}");
+        }
+
+        Ok(self.tree.add_dir(parent, name.to_owned()))
+    }
+
+    fn ls(&mut self, line: usize, dirs: Vec<String>, files: Vec<File>) -> Result<()> {
+        let path = self.tree.path(self.cwd);
+
+        let mut names = HashSet::new();
+        for name in dirs.iter().chain(files.iter().map(|f| &f.name)) {
+            ensure!(
+                names.insert(name),
+                "line {line}: {name:?} appears twice in the listing of {path}"
+            );
+        }
+
+        let dir = &mut self.tree.dirs[self.cwd];
+
+        // A repeat listing has to match the first one exactly.
+        if let Some(prev) = dir.listed_on {
+            let same_dirs = dirs.len() == dir.children.len()
+                && dirs.iter().all(|d| dir.children.contains_key(d));
+            let same_files =
+                files.len() == dir.files.len() && files.iter().all(|f| dir.files.contains(f));
+            ensure!(
+                same_dirs && same_files,
+                "line {line}: listing of {path} conflicts with the one on line {prev}"
+            );
+            return Ok(());
+        }
+
+        // Any subdirectories we've already been into must show up.
+        for name in dir.children.keys() {
+            ensure!(
+                dirs.contains(name),
+                "line {line}: listing of {path} is missing {name:?}, which was entered earlier"
+            );
+        }
+
+        dir.files = files;
+        dir.listed_on = Some(line);
+
+        for name in dirs {
+            if !self.tree.dirs[self.cwd].children.contains_key(&name) {
+                self.tree.add_dir(self.cwd, name);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        io::{BufReader, Cursor},
+    };
+
+    use anyhow::Result;
+
+    use crate::{
+        common::example,
+        y2022::day_7::{build_tree, parse_input, Tree},
+    };
+
+    fn tree(transcript: &str) -> Result<Tree> {
+        let commands = parse_input(BufReader::new(Cursor::new(transcript)))?;
+        build_tree(commands)
+    }
+
+    /// Directory sizes, keyed by path.
+    fn sizes_by_path(tree: &Tree) -> HashMap<String, usize> {
+        let sizes = tree.dir_sizes();
+        (0..sizes.len()).map(|i| (tree.path(i), sizes[i])).collect()
+    }
+
+    #[test]
+    fn relaxed_transcript_matches() -> Result<()> {
+        let expected = tree(example!(2022, 7))?;
+
+        let relaxed = "\
+$ cd /a/e
+$ ls
+584 i
+$ cd ../../d
+$ ls
+4060174 j
+8033020 d.log
+5626152 d.ext
+7214296 k
+$ ls
+7214296 k
+4060174 j
+8033020 d.log
+5626152 d.ext
+$ cd /
+$ ls
+dir a
+14848514 b.txt
+8504156 c.dat
+dir d
+$ cd a/e/.././
+$ ls
+dir e
+29116 f
+2557 g
+62596 h.lst
+";
+        let actual = tree(relaxed)?;
+
+        assert!(actual.is_fully_explored());
+        assert_eq!(sizes_by_path(&actual), sizes_by_path(&expected));
+        Ok(())
+    }
+
+    #[test]
+    fn partial_exploration() -> Result<()> {
+        let tree = tree("$ cd /a/b\n$ ls\n10 x\n")?;
+
+        assert!(!tree.is_fully_explored());
+        let listed: Vec<_> = tree.dirs().iter().map(|d| d.is_listed()).collect();
+        assert_eq!(listed, [false, false, true]);
+        assert_eq!(tree.path(2), "/a/b");
+        assert_eq!(tree.dir_sizes(), [10, 10, 10]);
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts() {
+        let err = |transcript: &str| tree(transcript).unwrap_err().to_string();
+
+        // The second listing disagrees about a file size.
+        assert_eq!(
+            err("$ ls\n1 a\n$ ls\n2 a\n"),
+            "line 3: listing of / conflicts with the one on line 1"
+        );
+        // cd into something the listing says doesn't exist.
+        assert_eq!(
+            err("$ ls\ndir a\n$ cd b\n"),
+            "line 3: can't cd into /b: it doesn't exist, according to the listing on line 1"
+        );
+        // cd into a file.
+        assert_eq!(
+            err("$ ls\n5 a\n$ cd a\n"),
+            "line 3: can't cd into /a: it is a file, according to the listing on line 1"
+        );
+        // A later listing leaves out a directory we were already in.
+        assert_eq!(
+            err("$ cd x\n$ cd /\n$ ls\ndir y\n"),
+            "line 3: listing of / is missing \"x\", which was entered earlier"
+        );
+        // The same name twice in one listing.
+        assert_eq!(
+            err("$ cd /q\n$ ls\ndir a\n1 a\n"),
+            "line 2: \"a\" appears twice in the listing of /q"
+        );
+    }
+}
@@ -0,0 +1,303 @@
+mod directions;
+mod heightmap;
+mod viewshed;
+
+use std::iter;
+
+use anyhow::{bail, Result};
+
+pub use self::{
+    directions::{Directions, Point, Step},
+    heightmap::Format,
+    viewshed::Viewshed,
+};
+use crate::common::grid::{check_rectangular, parse_grid};
+
+/// The number of trees visible from outside the grid.
+pub fn part_1(input: &str) -> Result<usize> {
+    let grid = Grid::parse(input)?;
+    Ok(grid.num_visible_trees())
+}
+
+/// The highest scenic score of any tree.
+pub fn part_2(input: &str) -> Result<usize> {
+    let grid = Grid::parse(input)?;
+    Ok(grid.best_score())
+}
+
+/// A non-empty, rectangular grid of tree heights. Puzzle grids are digits,
+/// but any ordered height type works, and some cells can be missing.
+#[derive(Debug)]
+pub struct Grid<H = u8> {
+    grid: Vec<Vec<H>>,
+    /// `mask[i][j]` is false if there's no tree there. `None` if every cell
+    /// has one.
+    mask: Option<Vec<Vec<bool>>>,
+}
+
+/// Helper for Grid::parse.
+fn char_to_digit(c: char) -> Result<u8> {
+    match c.to_digit(10) {
+        Some(d) => Ok(d as u8),
+        None => bail!("non-digit char {c:?}"),
+    }
+}
+
+impl Grid {
+    pub fn parse(input: &str) -> Result<Self> {
+        let grid = parse_grid(input, char_to_digit)?;
+        Ok(Grid { grid, mask: None })
+    }
+}
+
+impl<H: Ord + Copy> Grid<H> {
+    /// Fails if `rows` is empty or jagged.
+    pub fn new(rows: Vec<Vec<H>>) -> Result<Self> {
+        check_rectangular(&rows)?;
+        Ok(Grid {
+            grid: rows,
+            mask: None,
+        })
+    }
+
+    /// (width, height)
+    pub fn dims(&self) -> (usize, usize) {
+        let w = self.grid[0].len();
+        let h = self.grid.len();
+        (w, h)
+    }
+
+    /// The number of trees visible from outside the grid, looking along the
+    /// four axes.
+    pub fn num_visible_trees(&self) -> usize {
+        self.num_visible_from(&Directions::four())
+    }
+
+    /// The number of trees visible from outside the grid, looking back along
+    /// any of `dirs`.
+    pub fn num_visible_from(&self, dirs: &Directions) -> usize {
+        let visibility = self.visibility(dirs);
+        visibility.into_iter().flatten().filter(|&m| m != 0).count()
+    }
+
+    /// For each tree, a mask of the sides it's visible from: bit `k` is set if
+    /// every tree from it towards the edge, in direction `dirs.steps()[k]`, is
+    /// shorter.
+    ///
+    /// Idea: send a "probe" along each line of sight, from the edge inwards.
+    ///
+    /// When you see a tree that's taller than all the previous ones, it's
+    /// marked 'visible'. All other trees aren't visible (at least from that
+    /// direction), but you keep probing in case you run into another tall tree.
+    pub fn visibility(&self, dirs: &Directions) -> Vec<Vec<u64>> {
+        let (w, h) = self.dims();
+        let mut visibility = vec![vec![0; w]; h];
+
+        for (k, &step) in dirs.steps().iter().enumerate() {
+            for ray in self.rays(step) {
+                self.probe(ray, 1 << k, &mut visibility);
+            }
+        }
+
+        visibility
+    }
+
+    /// Helper for `visibility`.
+    fn probe(
+        &self,
+        mut coords: impl Iterator<Item = Point>,
+        bit: u64,
+        visibility: &mut [Vec<u64>],
+    ) {
+        let (i, j) = coords.next().unwrap();
+        let mut tallest_so_far = self.grid[i][j];
+        visibility[i][j] |= bit;
+
+        for (i, j) in coords {
+            if self.grid[i][j] > tallest_so_far {
+                visibility[i][j] |= bit;
+                tallest_so_far = self.grid[i][j];
+            }
+        }
+    }
+
+    pub fn best_score(&self) -> usize {
+        let scores = self.scores();
+        scores.into_iter().flatten().max().unwrap()
+    }
+
+    /// The scenic score of every tree, indexed `[i][j]` like the grid, looking
+    /// along the four axes.
+    pub fn scores(&self) -> Vec<Vec<usize>> {
+        self.scores_along(&Directions::four())
+    }
+
+    /// The scenic score of every tree, looking along each of `dirs`. Missing
+    /// cells score 0. With many directions, scores can get huge, so they stop
+    /// at `usize::MAX`.
+    ///
+    /// Idea: instead of walking outward from every tree, sweep each line of
+    /// sight once, keeping a stack of the trees that could still block the
+    /// view of trees further along. See `sweep`.
+    pub fn scores_along(&self, dirs: &Directions) -> Vec<Vec<usize>> {
+        let (w, h) = self.dims();
+        let mut scores = vec![vec![1; w]; h];
+
+        for &step in dirs.steps() {
+            for ray in self.rays(step) {
+                self.sweep(ray, &mut scores);
+            }
+        }
+
+        for (i, row) in scores.iter_mut().enumerate() {
+            for (j, score) in row.iter_mut().enumerate() {
+                if !self.exists((i, j)) {
+                    *score = 0;
+                }
+            }
+        }
+
+        scores
+    }
+
+    /// Helper for `scores_along`. Multiply each tree's score by its viewing
+    /// distance back towards the start of `coords`.
+    ///
+    /// The stack holds the positions of trees seen so far, with heights
+    /// non-increasing from bottom to top. A tree hides everything behind it
+    /// that's shorter, so when we reach a new tree we pop the shorter ones; the
+    /// new top (if any) is the tree that blocks its view. Each position is
+    /// pushed and popped at most once, so this is linear.
+    fn sweep(&self, coords: impl Iterator<Item = Point>, scores: &mut [Vec<usize>]) {
+        let mut stack: Vec<(usize, H)> = vec![];
+
+        for (k, (i, j)) in coords.enumerate() {
+            let height = self.grid[i][j];
+            while stack.pop_if(|&mut (_, h)| h < height).is_some() {}
+
+            let dist = match stack.last() {
+                Some(&(blocker, _)) => k - blocker,
+                // We can see all the way to the edge.
+                None => k,
+            };
+            scores[i][j] = scores[i][j].saturating_mul(dist);
+            stack.push((k, height));
+        }
+    }
+
+    /// The scenic score of the tree at row `i`, col `j`.
+    pub fn score(&self, i: usize, j: usize) -> usize {
+        self.score_along(i, j, &Directions::four())
+    }
+
+    /// The scenic score of the tree at row `i`, col `j`, looking along each of
+    /// `dirs`. Like `scores_along`, this stops at `usize::MAX`.
+    pub fn score_along(&self, i: usize, j: usize, dirs: &Directions) -> usize {
+        let Some(limit) = self.height((i, j)) else {
+            return 0;
+        };
+
+        // look in each direction, count trees (but stopping at/after the first tree that's as tall as us)
+        dirs.steps()
+            .iter()
+            .map(|&step| {
+                let ray = iter::successors(self.step((i, j), step), |&p| self.step(p, step));
+                self.probe_2(ray, limit)
+            })
+            .fold(1, usize::saturating_mul)
+    }
+
+    /// Helper for `score_along`.
+    fn probe_2(&self, coords: impl Iterator<Item = Point>, limit: H) -> usize {
+        let mut count = 0;
+        for (i, j) in coords {
+            count += 1;
+
+            // The first tall tree marks the end of our line-of-sight.
+            if self.grid[i][j] >= limit {
+                break;
+            }
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Directions, Grid};
+    use crate::common::{example, input, testing::XorShift};
+
+    #[test]
+    fn part_1() -> Result<()> {
+        dbg!(super::part_1(input!(2022, 8))?);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        dbg!(super::part_2(input!(2022, 8))?);
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, 8))?, 21);
+        assert_eq!(super::part_2(example!(2022, 8))?, 8);
+
+        let grid = Grid::parse(example!(2022, 8))?;
+        assert_eq!(grid.scores()[3][2], 8);
+        Ok(())
+    }
+
+    #[test]
+    fn scores_match_naive() -> Result<()> {
+        let mut rng = XorShift::new(8);
+
+        for _ in 0..200 {
+            let w = 1 + rng.below(12) as usize;
+            let h = 1 + rng.below(12) as usize;
+            // Sometimes few distinct heights (lots of ties), sometimes many.
+            let max_height = [2, 10, 1_000_000][rng.below(3) as usize];
+            let rows = (0..h)
+                .map(|_| (0..w).map(|_| rng.below(max_height) as u32).collect())
+                .collect();
+            let grid = Grid::new(rows)?;
+
+            let scores = grid.scores();
+            for (i, row) in scores.iter().enumerate() {
+                for (j, &score) in row.iter().enumerate() {
+                    assert_eq!(score, grid.score(i, j), "({i}, {j}) in {grid:?}");
+                }
+            }
+            assert_eq!(
+                grid.best_score(),
+                scores.into_iter().flatten().max().unwrap()
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn huge_scores() -> Result<()> {
+        let eight = Directions::eight();
+        let peak = |n: usize| {
+            let mut rows = vec![vec![0u8; n]; n];
+            rows[n / 2][n / 2] = 9;
+            Grid::new(rows)
+        };
+
+        // The peak sees 20 trees each way.
+        let grid = peak(41)?;
+        assert_eq!(grid.scores_along(&eight)[20][20], 20usize.pow(8));
+        assert_eq!(grid.score_along(20, 20, &eight), 20usize.pow(8));
+
+        // 300^8 doesn't fit.
+        let grid = peak(601)?;
+        assert_eq!(grid.scores_along(&eight)[300][300], usize::MAX);
+        assert_eq!(grid.score_along(300, 300, &eight), usize::MAX);
+        Ok(())
+    }
+}
@@ -0,0 +1,304 @@
+use std::{collections::HashSet, fmt::Write, iter};
+
+use anyhow::{ensure, Result};
+
+use super::Grid;
+use crate::common::helpers::checked_add;
+
+/// (row, col)
+pub type Point = (usize, usize);
+
+/// A line of sight, as a (row, col) step between consecutive cells along it.
+/// For example, (-1, 0) looks north, and (1, 2) is a knight's-move ray.
+pub type Step = (isize, isize);
+
+/// The directions trees look in, and are looked at from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Directions {
+    steps: Vec<Step>,
+}
+
+impl Directions {
+    /// At most this many, so a tree's sides fit in a `u64` mask.
+    pub const MAX: usize = 64;
+
+    /// Fails if there are no steps, too many, duplicates, or (0, 0).
+    pub fn new(steps: Vec<Step>) -> Result<Self> {
+        ensure!(!steps.is_empty(), "no directions");
+        ensure!(
+            steps.len() <= Self::MAX,
+            "at most {} directions allowed, got {}",
+            Self::MAX,
+            steps.len()
+        );
+        ensure!(!steps.contains(&(0, 0)), "(0, 0) isn't a direction");
+
+        let mut seen = HashSet::new();
+        for &step in &steps {
+            ensure!(seen.insert(step), "duplicate direction {step:?}");
+        }
+
+        Ok(Self { steps })
+    }
+
+    /// North, east, south and west, as in the puzzle.
+    pub fn four() -> Self {
+        Self {
+            steps: vec![(-1, 0), (0, 1), (1, 0), (0, -1)],
+        }
+    }
+
+    /// Clockwise from north, including the diagonals.
+    pub fn eight() -> Self {
+        Self {
+            steps: vec![
+                (-1, 0),
+                (-1, 1),
+                (0, 1),
+                (1, 1),
+                (1, 0),
+                (1, -1),
+                (0, -1),
+                (-1, -1),
+            ],
+        }
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+}
+
+impl<H: Ord + Copy> Grid<H> {
+    /// `p + step`, if that's still inside the grid and isn't missing.
+    pub(super) fn step(&self, (i, j): Point, (di, dj): Step) -> Option<Point> {
+        let (w, h) = self.dims();
+        let i2 = checked_add(i, di)?;
+        let j2 = checked_add(j, dj)?;
+        (i2 < h && j2 < w && self.exists((i2, j2))).then_some((i2, j2))
+    }
+
+    /// Every line of sight running in direction `step`, each listed backwards:
+    /// starting from the cell nearest the edge (or a missing cell), and
+    /// heading away from it.
+    ///
+    /// Together they cover each existing cell exactly once.
+    pub(super) fn rays(
+        &self,
+        step: Step,
+    ) -> impl Iterator<Item = impl Iterator<Item = Point> + '_> + '_ {
+        let (w, h) = self.dims();
+        let back = (-step.0, -step.1);
+
+        (0..h)
+            .flat_map(move |i| (0..w).map(move |j| (i, j)))
+            .filter(move |&p| self.exists(p) && self.step(p, step).is_none())
+            .map(move |start| iter::successors(Some(start), move |&p| self.step(p, back)))
+    }
+
+    /// Draw each tree as a 3x3 block: `*` in the middle if it's visible from
+    /// anywhere (`.` if not), surrounded by a mark on each side it's visible
+    /// from. Missing cells are left blank.
+    ///
+    /// Marks go in the compass position of the direction's sign, so a custom
+    /// step like (1, 2) shares a mark with (1, 1).
+    ///
+    /// For example, a tall tree in the middle of a 3x3 grid of short ones:
+    ///
+    /// ```text
+    ///  ^   ^   ^
+    /// <*   *   *>
+    ///
+    ///
+    ///      ^
+    /// <*  <*>  *>
+    ///      v
+    ///
+    ///
+    /// <*   *   *>
+    ///  v   v   v
+    /// ```
+    pub fn render_visibility(&self, dirs: &Directions) -> String {
+        let visibility = self.visibility(dirs);
+        let mut out = String::new();
+
+        for (i, row) in visibility.iter().enumerate() {
+            if i != 0 {
+                out.push('\n');
+            }
+
+            let mut lines = [String::new(), String::new(), String::new()];
+            for (j, &mask) in row.iter().enumerate() {
+                let mut block = [[' '; 3]; 3];
+                block[1][1] = match mask {
+                    _ if !self.exists((i, j)) => ' ',
+                    0 => '.',
+                    _ => '*',
+                };
+
+                for (k, &(di, dj)) in dirs.steps().iter().enumerate() {
+                    if mask & (1 << k) != 0 {
+                        let (r, c) = (di.signum(), dj.signum());
+                        block[(r + 1) as usize][(c + 1) as usize] = match (r, c) {
+                            (-1, 0) => '^',
+                            (1, 0) => 'v',
+                            (0, -1) => '<',
+                            (0, 1) => '>',
+                            (-1, -1) | (1, 1) => '\\',
+                            _ => '/',
+                        };
+                    }
+                }
+
+                for (line, block_row) in lines.iter_mut().zip(block) {
+                    if j != 0 {
+                        line.push(' ');
+                    }
+                    line.extend(block_row);
+                }
+            }
+
+            for line in lines {
+                writeln!(out, "{}", line.trim_end()).unwrap();
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use anyhow::Result;
+
+    use super::{Directions, Grid, Step};
+    use crate::common::{example, testing::XorShift};
+
+    #[test]
+    fn directions() {
+        assert!(Directions::new(vec![]).is_err());
+        assert!(Directions::new(vec![(0, 0)]).is_err());
+        assert!(Directions::new(vec![(1, 2), (1, 2)]).is_err());
+        assert!(Directions::new(vec![(1, 0); 65]).is_err());
+        assert!(Directions::new(vec![(1, 2), (-3, 0)]).is_ok());
+    }
+
+    #[test]
+    fn eight_way_example() -> Result<()> {
+        let grid = Grid::parse(example!(2022, 8))?;
+        let eight = Directions::eight();
+
+        // Looking diagonally too can only reveal more trees.
+        assert!(grid.num_visible_from(&eight) >= grid.num_visible_trees());
+
+        // The axis bits are the same as when looking four ways.
+        let four = grid.visibility(&Directions::four());
+        let visibility = grid.visibility(&eight);
+        for (row, row_4) in visibility.iter().zip(&four) {
+            for (&mask, &mask_4) in row.iter().zip(row_4) {
+                let axes = (0..4).map(|k| (mask >> (2 * k) & 1) << k);
+                assert_eq!(axes.sum::<u64>(), mask_4);
+            }
+        }
+
+        let scores = grid.scores_along(&eight);
+        for (i, row) in scores.iter().enumerate() {
+            for (j, &score) in row.iter().enumerate() {
+                assert_eq!(score, grid.score_along(i, j, &eight));
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn render() -> Result<()> {
+        let grid = Grid::parse("000\n090\n000")?;
+        let expected = [
+            " ^   ^   ^",
+            "<*   *   *>",
+            "",
+            "",
+            "     ^",
+            "<*  <*>  *>",
+            "     v",
+            "",
+            "",
+            "<*   *   *>",
+            " v   v   v",
+        ];
+        assert_eq!(
+            grid.render_visibility(&Directions::four()),
+            expected.join("\n") + "\n"
+        );
+
+        let diagonal = Directions::new(vec![(-1, -1), (1, 2)])?;
+        let rendered = grid.render_visibility(&diagonal);
+        let expected = [
+            "\\   \\   \\",
+            " *   *   *",
+            "      \\   \\",
+            "",
+            "\\   \\",
+            " *   *   *",
+            "      \\   \\",
+            "",
+            "\\",
+            " *   *   *",
+            "  \\   \\   \\",
+        ];
+        assert_eq!(rendered, expected.join("\n") + "\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_directions_match_naive() -> Result<()> {
+        let mut rng = XorShift::new(41);
+        let random_step = |rng: &mut XorShift| -> Step {
+            loop {
+                let step = (rng.below(7) as isize - 3, rng.below(7) as isize - 3);
+                if step != (0, 0) {
+                    return step;
+                }
+            }
+        };
+
+        for _ in 0..100 {
+            let w = 1 + rng.below(9) as usize;
+            let h = 1 + rng.below(9) as usize;
+            let rows = (0..h)
+                .map(|_| (0..w).map(|_| rng.below(6) as u16).collect())
+                .collect();
+            let grid = Grid::new(rows)?;
+
+            let mut steps: Vec<Step> = vec![];
+            for _ in 0..1 + rng.below(5) {
+                let step = random_step(&mut rng);
+                if !steps.contains(&step) {
+                    steps.push(step);
+                }
+            }
+            let dirs = Directions::new(steps)?;
+
+            let visibility = grid.visibility(&dirs);
+            let scores = grid.scores_along(&dirs);
+            for i in 0..h {
+                for j in 0..w {
+                    assert_eq!(scores[i][j], grid.score_along(i, j, &dirs));
+
+                    for (k, &step) in dirs.steps().iter().enumerate() {
+                        let mut ray =
+                            iter::successors(grid.step((i, j), step), |&p| grid.step(p, step));
+                        let naive = ray.all(|(i2, j2)| grid.grid[i2][j2] < grid.grid[i][j]);
+                        assert_eq!(visibility[i][j] & (1 << k) != 0, naive);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
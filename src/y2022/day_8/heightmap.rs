@@ -0,0 +1,208 @@
+use std::{fmt::Debug, str::FromStr};
+
+use anyhow::{bail, ensure, Context, Result};
+
+use super::{Grid, Point};
+use crate::common::grid::check_rectangular;
+
+/// How a heightmap is written down. Each line is a row.
+///
+/// Missing cells are written as `.` (or, in CSV, as an empty field). Rows can
+/// be different lengths; short rows are padded with missing cells.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// One digit per cell, like the puzzle input.
+    Digits,
+    /// Integers separated by spaces or tabs.
+    Whitespace,
+    /// Integers separated by commas.
+    Csv,
+}
+
+impl Format {
+    /// Guess the format: CSV if there are any commas, whitespace-separated if
+    /// any line has more than one token, and digits otherwise.
+    pub fn detect(input: &str) -> Self {
+        if input.contains(',') {
+            Self::Csv
+        } else if input.lines().any(|l| l.split_whitespace().nth(1).is_some()) {
+            Self::Whitespace
+        } else {
+            Self::Digits
+        }
+    }
+
+    /// Helper for `Grid::parse_as`. The cells of one line, with `None` for
+    /// missing ones.
+    fn cells<'a>(self, line: &'a str) -> Vec<Option<&'a str>> {
+        let cell = |s: &'a str| match s {
+            "." | "" => None,
+            s => Some(s),
+        };
+
+        match self {
+            Self::Digits => line
+                .char_indices()
+                .map(|(k, c)| cell(&line[k..k + c.len_utf8()]))
+                .collect(),
+            Self::Whitespace => line.split_whitespace().map(cell).collect(),
+            Self::Csv if line.trim().is_empty() => vec![],
+            Self::Csv => line.split(',').map(|s| cell(s.trim())).collect(),
+        }
+    }
+}
+
+impl<H> Grid<H>
+where
+    H: Ord + Copy + Default + FromStr,
+    H::Err: Debug,
+{
+    /// Parse a heightmap with any integer heights, e.g. `Grid::<u32>`, and
+    /// possibly with missing cells.
+    pub fn parse_as(input: &str, format: Format) -> Result<Self> {
+        let mut rows = vec![];
+        let mut mask = vec![];
+
+        for (line, line_num) in input.lines().zip(1..) {
+            let mut row = vec![];
+            let mut row_mask = vec![];
+
+            for (cell, col) in format.cells(line).into_iter().zip(1..) {
+                let height = match cell {
+                    Some(s) => {
+                        let valid =
+                            format != Format::Digits || s.chars().all(|c| c.is_ascii_digit());
+                        let height = s.parse().ok().filter(|_| valid);
+                        Some(height.with_context(|| {
+                            format!("line {line_num}, cell {col}: invalid height {s:?}")
+                        })?)
+                    }
+                    None => None,
+                };
+                row.push(height.unwrap_or_default());
+                row_mask.push(height.is_some());
+            }
+
+            rows.push(row);
+            mask.push(row_mask);
+        }
+
+        // Pad out the short rows.
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        for (row, row_mask) in rows.iter_mut().zip(&mut mask) {
+            row.resize(width, H::default());
+            row_mask.resize(width, false);
+        }
+
+        Self::with_mask(rows, mask)
+    }
+}
+
+impl<H: Ord + Copy> Grid<H> {
+    /// Like `new`, but only cells where `mask` is true exist. Fails unless
+    /// `rows` and `mask` are rectangular with the same dimensions, and at least
+    /// one cell exists.
+    pub fn with_mask(rows: Vec<Vec<H>>, mask: Vec<Vec<bool>>) -> Result<Self> {
+        check_rectangular(&rows)?;
+        ensure!(
+            mask.len() == rows.len() && mask.iter().all(|m| m.len() == rows[0].len()),
+            "the mask and the heights have different dimensions"
+        );
+        if !mask.iter().flatten().any(|&b| b) {
+            bail!("every cell is missing");
+        }
+
+        let mask = (!mask.iter().flatten().all(|&b| b)).then_some(mask);
+        Ok(Grid { grid: rows, mask })
+    }
+
+    /// Whether there's a tree at `p`.
+    ///
+    /// Looking along `Directions`, a missing cell is like the edge of the
+    /// grid: a tree next to one can be seen from that side, and its own view
+    /// that way stops there. (`viewshed` is different, and looks straight
+    /// across missing cells.)
+    pub fn exists(&self, (i, j): Point) -> bool {
+        match &self.mask {
+            Some(mask) => mask[i][j],
+            None => true,
+        }
+    }
+
+    /// The height of the tree at `p`, if there is one.
+    pub fn height(&self, p: Point) -> Option<H> {
+        self.exists(p).then(|| self.grid[p.0][p.1])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Format, Grid};
+    use crate::y2022::day_8::Directions;
+
+    #[test]
+    fn formats() -> Result<()> {
+        let digits = "30373\n25512\n65332\n33549\n35390\n";
+        let whitespace = "3 0 3 7 3\n2 5 5 1 2\n6 5 3 3 2\n3 3 5 4 9\n3\t5 3  9 0\n";
+        let csv = "3,0,3,7,3\n2,5,5,1,2\n6,5,3,3,2\n3,3,5,4,9\n3, 5, 3, 9, 0\n";
+
+        for (input, format) in [
+            (digits, Format::Digits),
+            (whitespace, Format::Whitespace),
+            (csv, Format::Csv),
+        ] {
+            assert_eq!(Format::detect(input), format);
+            let grid = Grid::<u16>::parse_as(input, format)?;
+            assert_eq!(grid.num_visible_trees(), 21);
+            assert_eq!(grid.best_score(), 8);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_heights() -> Result<()> {
+        let grid = Grid::<u32>::parse_as("100000 7 100001\n5 70000 5", Format::Whitespace)?;
+        assert_eq!(grid.height((0, 2)), Some(100_001));
+        // Everything's on the edge.
+        assert_eq!(grid.num_visible_trees(), 6);
+
+        assert!(Grid::<u16>::parse_as("100000", Format::Whitespace).is_err());
+        assert!(Grid::<u16>::parse_as("1 -2", Format::Whitespace).is_err());
+        assert!(Grid::<u16>::parse_as("1+", Format::Digits).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_cells() -> Result<()> {
+        // A ring of 1s around a hole, inside a wall of 9s.
+        let input = "99999\n91119\n91.19\n91119\n99999";
+        let grid = Grid::<u8>::parse_as(input, Format::Digits)?;
+        assert_eq!(grid.height((2, 2)), None);
+
+        // The 1s next to the hole can be seen from it, but not the corners.
+        assert_eq!(grid.num_visible_trees(), 16 + 4);
+        let eight = Directions::eight();
+        assert_eq!(grid.num_visible_from(&eight), 16 + 8);
+
+        // Missing cells end lines of sight, like the edge does.
+        let scores = grid.scores();
+        assert_eq!(scores[1][1], 1);
+        assert_eq!(scores[2][1], 0);
+        assert_eq!(scores[2][2], 0);
+        assert_eq!(grid.score(2, 1), 0);
+
+        // Jagged rows and CSV gaps.
+        let grid = Grid::<u8>::parse_as("5,,5\n1\n", Format::Csv)?;
+        assert_eq!(grid.dims(), (3, 2));
+        assert_eq!(grid.height((0, 1)), None);
+        assert_eq!(grid.height((1, 2)), None);
+        assert_eq!(grid.num_visible_trees(), 3);
+
+        assert!(Grid::<u8>::parse_as("..\n.", Format::Digits).is_err());
+        assert!(Grid::<u8>::parse_as("", Format::Digits).is_err());
+        Ok(())
+    }
+}
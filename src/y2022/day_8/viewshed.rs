@@ -0,0 +1,195 @@
+use anyhow::{ensure, Context, Result};
+
+use super::{Grid, Point};
+
+/// Everything an observer can see. See `Grid::viewshed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Viewshed {
+    /// `visible[i][j]` is true if the top of the tree at (i, j) can be seen.
+    pub visible: Vec<Vec<bool>>,
+    /// The number of true cells in `visible`, including the observer's own.
+    pub count: usize,
+}
+
+impl<H: Ord + Copy + Into<i64>> Grid<H> {
+    /// Which trees can be seen by someone standing on top of the tree at
+    /// `observer`, with their eyes `eye_height` above it.
+    ///
+    /// Trees are columns, rising from the center of their cell. We trace a
+    /// Bresenham line from the observer to each target; the target is visible
+    /// unless a tree along the way pokes strictly above the straight sight
+    /// line from the observer's eyes to the top of the target. Each step along
+    /// the line moves one cell along its major axis, so the sight line's height
+    /// there is an exact fraction, and we compare without rounding.
+    ///
+    /// Missing cells can't be seen, and never block anything.
+    pub fn viewshed(&self, observer: Point, eye_height: i64) -> Result<Viewshed> {
+        let (w, h) = self.dims();
+        let (oi, oj) = observer;
+        ensure!(
+            oi < h && oj < w,
+            "observer {observer:?} is outside the grid"
+        );
+        let ground = self
+            .height(observer)
+            .with_context(|| format!("no tree at observer {observer:?}"))?;
+        let eye = ground.into() as i128 + eye_height as i128;
+
+        let mut visible = vec![vec![false; w]; h];
+        let mut count = 0;
+
+        for (i, row) in visible.iter_mut().enumerate() {
+            for (j, cell) in row.iter_mut().enumerate() {
+                let Some(target) = self.height((i, j)) else {
+                    continue;
+                };
+                let target = target.into();
+
+                let line = bresenham(observer, (i, j));
+                let n = (line.len() - 1) as i128;
+                let target = target as i128;
+
+                // At step k, the sight line is at eye + (target - eye) * k / n.
+                let inner = line.get(1..line.len() - 1).unwrap_or_default();
+                let blocked = inner.iter().zip(1..).any(|(&p, k)| match self.height(p) {
+                    Some(height) => height.into() as i128 * n > eye * n + (target - eye) * k,
+                    None => false,
+                });
+
+                if !blocked {
+                    *cell = true;
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(Viewshed { visible, count })
+    }
+}
+
+/// The cells on the line from `from` to `to`, inclusive, in order. Each one is
+/// a single step further along the major axis than the last.
+fn bresenham(from: Point, to: Point) -> Vec<Point> {
+    let (mut i, mut j) = (from.0 as isize, from.1 as isize);
+    let (i1, j1) = (to.0 as isize, to.1 as isize);
+
+    let di = (i1 - i).abs();
+    let dj = -(j1 - j).abs();
+    let si = if i < i1 { 1 } else { -1 };
+    let sj = if j < j1 { 1 } else { -1 };
+    let mut err = di + dj;
+
+    let mut line = vec![];
+    loop {
+        line.push((i as usize, j as usize));
+        if (i, j) == (i1, j1) {
+            return line;
+        }
+
+        let e2 = 2 * err;
+        if e2 >= dj {
+            err += dj;
+            i += si;
+        }
+        if e2 <= di {
+            err += di;
+            j += sj;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{bresenham, Grid};
+    use crate::{
+        common::{example, testing::XorShift},
+        y2022::day_8::Format,
+    };
+
+    fn render(visible: &[Vec<bool>]) -> String {
+        let rows = visible.iter().map(|row| {
+            row.iter()
+                .map(|&v| if v { '#' } else { '.' })
+                .collect::<String>()
+        });
+        rows.collect::<Vec<_>>().join("\n")
+    }
+
+    #[test]
+    fn lines() {
+        assert_eq!(bresenham((0, 0), (0, 0)), [(0, 0)]);
+        assert_eq!(bresenham((2, 0), (0, 0)), [(2, 0), (1, 0), (0, 0)]);
+        assert_eq!(bresenham((0, 0), (1, 3)), [(0, 0), (0, 1), (1, 2), (1, 3)]);
+        assert_eq!(bresenham((1, 3), (0, 0)), [(1, 3), (1, 2), (0, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn flat_ground() -> Result<()> {
+        let grid = Grid::parse(example!(2022, 8))?;
+        let flat = Grid::<u8>::new(vec![vec![4; 5]; 5])?;
+        let viewshed = flat.viewshed((2, 2), 0)?;
+        assert_eq!(viewshed.count, 25);
+
+        // From the tallest tree, high enough up, we can see everything.
+        assert_eq!(grid.viewshed((3, 4), 100)?.count, 25);
+
+        assert!(grid.viewshed((5, 0), 0).is_err());
+
+        // Sky-high or deep underground, without overflowing.
+        assert_eq!(grid.viewshed((0, 0), i64::MAX)?.count, 25);
+        assert_eq!(grid.viewshed((2, 2), i64::MIN)?.count, 9);
+        Ok(())
+    }
+
+    #[test]
+    fn walls() -> Result<()> {
+        let grid = Grid::<u8>::parse_as("11511\n11511\n11.11\n11511\n11511", Format::Digits)?;
+
+        // We can see the wall, and through the gap in it.
+        let viewshed = grid.viewshed((2, 0), 1)?;
+        let expected = [
+            "###..", //
+            "###..", //
+            "##.##", //
+            "###..", //
+            "###..",
+        ];
+        assert_eq!(render(&viewshed.visible), expected.join("\n"));
+        assert_eq!(viewshed.count, 16);
+
+        // High enough up, we can see over the wall.
+        assert_eq!(grid.viewshed((2, 0), 100)?.count, 24);
+        Ok(())
+    }
+
+    #[test]
+    fn higher_sees_more() -> Result<()> {
+        let mut rng = XorShift::new(43);
+
+        for _ in 0..50 {
+            let w = 1 + rng.below(10) as usize;
+            let h = 1 + rng.below(10) as usize;
+            let rows = (0..h)
+                .map(|_| (0..w).map(|_| rng.below(20) as u16).collect())
+                .collect();
+            let grid = Grid::new(rows)?;
+            let observer = (rng.below(h as u64) as usize, rng.below(w as u64) as usize);
+
+            let mut prev = grid.viewshed(observer, 0)?;
+            assert!(prev.visible[observer.0][observer.1]);
+            for eye_height in 1..10 {
+                let curr = grid.viewshed(observer, eye_height)?;
+                for (row, prev_row) in curr.visible.iter().zip(&prev.visible) {
+                    for (&v, &prev_v) in row.iter().zip(prev_row) {
+                        assert!(v || !prev_v);
+                    }
+                }
+                prev = curr;
+            }
+        }
+
+        Ok(())
+    }
+}
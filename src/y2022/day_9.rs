@@ -0,0 +1,294 @@
+mod render;
+mod rope;
+mod trail;
+
+use std::{
+    fmt::{self, Display},
+    iter,
+    ops::{Add, Sub},
+};
+
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+
+pub use self::{
+    render::{Every, Frame},
+    rope::{Follow, ManhattanOnly, MaxSlack, Rope, Touching, LIMIT},
+    trail::Trail,
+};
+use crate::common::helpers::until_err;
+
+/// The number of positions visited by the tail of a 2-knot rope.
+pub fn part_1(input: &str) -> Result<usize> {
+    let mut err = Ok(());
+    let motions = parse_input(input).scan(&mut err, until_err);
+    let answer = simulate(motions);
+    err?;
+
+    answer
+}
+
+/// The number of positions visited by the tail of a 10-knot rope.
+pub fn part_2(input: &str) -> Result<usize> {
+    let mut err = Ok(());
+    let motions = parse_input(input).scan(&mut err, until_err);
+    let answer = simulate_part_2(motions);
+    err?;
+
+    answer
+}
+
+pub fn parse_input(input: &str) -> impl Iterator<Item = Result<Motion>> + '_ {
+    input
+        .lines()
+        .zip(1..)
+        .map(|(line, line_num)| parse_line(line).with_context(|| format!("line {line_num}")))
+}
+
+/// Either a direction and an amount, like `R 4`, `UL 2` or `LEFT 3`, or an
+/// absolute target for the head, like `TO 3,-2`.
+pub fn parse_line(line: &str) -> Result<Motion> {
+    if let Some(target) = line.trim().strip_prefix("TO ") {
+        let (x, y) = target
+            .split(',')
+            .map(str::trim)
+            .collect_tuple()
+            .with_context(|| format!("expected a target like 3,-2, not {target:?}"))?;
+        let target = Point {
+            x: x.parse()?,
+            y: y.parse()?,
+        };
+        return Ok(Motion::To { target });
+    }
+
+    let (dir, amount) = line
+        .split_whitespace()
+        .collect_tuple()
+        .context("expected two words")?;
+
+    let (x, y) = match dir {
+        "L" | "LEFT" => (-1, 0),
+        "R" | "RIGHT" => (1, 0),
+        "D" | "DOWN" => (0, -1),
+        "U" | "UP" => (0, 1),
+        "UL" | "UP-LEFT" => (-1, 1),
+        "UR" | "UP-RIGHT" => (1, 1),
+        "DL" | "DOWN-LEFT" => (-1, -1),
+        "DR" | "DOWN-RIGHT" => (1, -1),
+        _ => bail!("not a direction code: {dir:?}"),
+    };
+
+    let amount: u32 = amount.parse()?;
+
+    Ok(Motion::Steps {
+        dir: Point { x, y },
+        amount,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    /// Move the head by `dir`, `amount` times. `dir` can be any vector, though
+    /// the parser only produces the eight unit ones.
+    Steps { dir: Point, amount: u32 },
+    /// Move the head to `target`, one unit step at a time: diagonally until
+    /// it's lined up, and then straight.
+    To { target: Point },
+}
+
+impl Motion {
+    /// The vectors the head moves by, one per step, if it starts at `head`.
+    pub fn steps(self, head: Point) -> impl Iterator<Item = Point> {
+        self.runs(head)
+            .flat_map(|(dir, n)| iter::repeat_n(dir, n as usize))
+    }
+
+    /// The same steps as `steps`, grouped into runs of (dir, count).
+    pub fn runs(self, head: Point) -> impl Iterator<Item = (Point, u32)> {
+        let runs = match self {
+            Self::Steps { dir, amount } => [(dir, amount), (dir, 0)],
+            Self::To { target } => {
+                // The gap might not fit in an `i32`, but its size fits in a `u32`.
+                let gap_x = target.x as i64 - head.x as i64;
+                let gap_y = target.y as i64 - head.y as i64;
+                let (dx, dy) = (gap_x.unsigned_abs() as u32, gap_y.unsigned_abs() as u32);
+                let diagonal = Point {
+                    x: gap_x.signum() as i32,
+                    y: gap_y.signum() as i32,
+                };
+                let straight = match dx > dy {
+                    true => Point {
+                        x: diagonal.x,
+                        y: 0,
+                    },
+                    false => Point {
+                        x: 0,
+                        y: diagonal.y,
+                    },
+                };
+                [(diagonal, dx.min(dy)), (straight, dx.abs_diff(dy))]
+            }
+        };
+        runs.into_iter().filter(|&(_, n)| n != 0)
+    }
+}
+
+/// As it would be written in the input, with short direction codes. Steps
+/// that aren't one of the eight unit vectors are written like `BY 2,1 3`,
+/// which the parser doesn't accept.
+impl Display for Motion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::Steps { dir, amount } => {
+                let code = match (dir.x, dir.y) {
+                    (-1, 0) => "L",
+                    (1, 0) => "R",
+                    (0, -1) => "D",
+                    (0, 1) => "U",
+                    (-1, 1) => "UL",
+                    (1, 1) => "UR",
+                    (-1, -1) => "DL",
+                    (1, -1) => "DR",
+                    (x, y) => return write!(f, "BY {x},{y} {amount}"),
+                };
+                write!(f, "{code} {amount}")
+            }
+            Self::To { target } => write!(f, "TO {},{}", target.x, target.y),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub const ORIGIN: Self = Self { x: 0, y: 0 };
+
+    /// `self + dir * n`, unless that doesn't fit in an `i32`.
+    pub fn checked_shift(self, dir: Point, n: u32) -> Option<Point> {
+        let shift = |a: i32, d: i32| {
+            let b = (d as i64).checked_mul(n as i64)?.checked_add(a as i64)?;
+            i32::try_from(b).ok()
+        };
+        Some(Point {
+            x: shift(self.x, dir.x)?,
+            y: shift(self.y, dir.y)?,
+        })
+    }
+}
+
+impl Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            x: self.x + other.x,
+            y: self.y + other.y,
+        }
+    }
+}
+
+impl Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Self {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+}
+
+/// Return the number of positions visited by "tail".
+pub fn simulate(motions: impl Iterator<Item = Motion>) -> Result<usize> {
+    let mut rope = Rope::new(2, Touching)?;
+    rope.run(motions)?;
+    Ok(rope.num_visited())
+}
+
+/// Return the number of positions visited by the tail of a 10-knot rope.
+pub fn simulate_part_2(motions: impl Iterator<Item = Motion>) -> Result<usize> {
+    let mut rope = Rope::new(10, Touching)?;
+    rope.run(motions)?;
+    Ok(rope.num_visited())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{parse_input, parse_line, Motion, Point, Rope, Touching};
+    use crate::common::{example, input};
+
+    #[test]
+    fn part_1() -> Result<()> {
+        let answer = super::part_1(input!(2022, 9))?;
+        dbg!(answer);
+        Ok(())
+    }
+
+    #[test]
+    fn part_2() -> Result<()> {
+        let answer = super::part_2(input!(2022, 9))?;
+        dbg!(answer);
+        Ok(())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        assert_eq!(super::part_1(example!(2022, "9-1"))?, 13);
+        assert_eq!(super::part_2(example!(2022, "9-1"))?, 1);
+        assert_eq!(super::part_2(example!(2022, "9-2"))?, 36);
+        Ok(())
+    }
+
+    #[test]
+    fn motion_grammar() -> Result<()> {
+        let p = |x, y| Point { x, y };
+        let steps = |dir, amount| Motion::Steps { dir, amount };
+
+        assert_eq!(parse_line("R 4")?, steps(p(1, 0), 4));
+        assert_eq!(parse_line("LEFT 4")?, steps(p(-1, 0), 4));
+        assert_eq!(parse_line("UL 2")?, steps(p(-1, 1), 2));
+        assert_eq!(parse_line("DOWN-RIGHT 1")?, steps(p(1, -1), 1));
+        assert_eq!(parse_line("TO 3,-2")?, Motion::To { target: p(3, -2) });
+        assert_eq!(parse_line("TO -1, 0")?, Motion::To { target: p(-1, 0) });
+
+        for bad in ["X 1", "L", "L -1", "L 1 2", "TO 3", "TO a,b", "TO 1,2,3"] {
+            assert!(parse_line(bad).is_err(), "{bad:?}");
+        }
+        let err = parse_input("R 1\nQ 2")
+            .collect::<Result<Vec<_>>>()
+            .unwrap_err();
+        assert!(format!("{err:#}").starts_with("line 2"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn targets() -> Result<()> {
+        let p = |x, y| Point { x, y };
+
+        // Diagonally until lined up, then straight.
+        let to = Motion::To { target: p(3, -2) };
+        let path: Vec<_> = to.steps(p(0, 0)).collect();
+        assert_eq!(path, [p(1, -1), p(1, -1), p(1, 0)]);
+        assert_eq!(to.steps(p(3, -2)).count(), 0);
+
+        // Going somewhere with TO is the same as spelling out the moves.
+        let spelled = parse_input("UR 2\nU 3\nDOWN-LEFT 5").collect::<Result<Vec<_>>>()?;
+        let targets = parse_input("TO 2,5\nTO -3,0").collect::<Result<Vec<_>>>()?;
+
+        let mut a = Rope::new(10, Touching)?;
+        a.run(spelled)?;
+        let mut b = Rope::new(10, Touching)?;
+        b.run(targets)?;
+        assert_eq!(a.knots(), b.knots());
+        assert_eq!(a.visited().to_set(), b.visited().to_set());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,275 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+
+use super::{Follow, Motion, Point, Rope};
+
+/// Which part of the plane to draw, and what to draw in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    /// The bottom-left corner, inclusive.
+    pub min: Point,
+    /// The top-right corner, inclusive.
+    pub max: Point,
+    /// Draw the knots: `H`, then `1`-`9` (or just `T` for a two-knot rope).
+    pub knots: bool,
+    /// Draw the cells the tracked knot has visited, as `#`.
+    pub trail: bool,
+}
+
+/// How often `Rope::animate` draws a frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Every {
+    Step,
+    Motion,
+}
+
+impl<F: Follow> Rope<F> {
+    /// Just big enough for the start, the knots and the visited cells, with
+    /// everything drawn.
+    pub fn frame(&self) -> Frame {
+        let (mut min, mut max) = self
+            .visited()
+            .bounds()
+            .unwrap_or((Point::ORIGIN, Point::ORIGIN));
+        for p in self.knots().iter().chain([&Point::ORIGIN]) {
+            min.x = min.x.min(p.x);
+            min.y = min.y.min(p.y);
+            max.x = max.x.max(p.x);
+            max.y = max.y.max(p.y);
+        }
+
+        Frame {
+            min,
+            max,
+            knots: true,
+            trail: true,
+        }
+    }
+
+    /// Draw the rope the way the puzzle does, with up at the top. The start is
+    /// `s`, and everything else is `.`.
+    ///
+    /// When several things share a cell, knots nearer the head win, then the
+    /// start, and then the trail.
+    pub fn render(&self, frame: &Frame) -> String {
+        let mut out = String::new();
+
+        for y in (frame.min.y..=frame.max.y).rev() {
+            for x in frame.min.x..=frame.max.x {
+                let p = Point { x, y };
+                let knot = match frame.knots {
+                    true => self.knots().iter().position(|&k| k == p),
+                    false => None,
+                };
+
+                let c = match knot {
+                    Some(i) => self.label(i),
+                    None if p == Point::ORIGIN => 's',
+                    None if frame.trail && self.visited().contains(p) => '#',
+                    None => '.',
+                };
+                out.push(c);
+            }
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Helper for `render`. Knots past 9 can't be told apart, except the tail.
+    fn label(&self, i: usize) -> char {
+        let n = self.knots().len();
+        match i {
+            0 => 'H',
+            _ if i == n - 1 && (n == 2 || i > 9) => 'T',
+            1..=9 => char::from_digit(i as u32, 10).unwrap(),
+            _ => '*',
+        }
+    }
+
+    /// Run `motions`, drawing a frame after every step or every motion. With
+    /// no `frame`, each one is as big as it needs to be at the time.
+    ///
+    /// Stops at the first step that fails; see `Rope::step`.
+    pub fn animate(
+        &mut self,
+        motions: impl IntoIterator<Item = Motion>,
+        every: Every,
+        frame: Option<Frame>,
+    ) -> Result<Vec<String>> {
+        let mut frames = vec![];
+        let draw = |rope: &Self| {
+            let frame = frame.unwrap_or_else(|| rope.frame());
+            rope.render(&frame)
+        };
+
+        for m in motions {
+            for dir in m.steps(self.head()) {
+                self.step(dir)?;
+                if every == Every::Step {
+                    frames.push(draw(self));
+                }
+            }
+            if every == Every::Motion {
+                frames.push(draw(self));
+            }
+        }
+
+        Ok(frames)
+    }
+
+    /// Like `animate`, but as one string, with each frame headed by the motion
+    /// that led to it (or, every step, its number within that motion).
+    pub fn trace(
+        &mut self,
+        motions: impl IntoIterator<Item = Motion>,
+        every: Every,
+    ) -> Result<String> {
+        let mut out = String::new();
+
+        for m in motions {
+            let frames = self.animate([m], every, None)?;
+            writeln!(out, "== {m} ==").unwrap();
+            for (i, frame) in frames.iter().enumerate() {
+                if every == Every::Step {
+                    writeln!(out, "-- step {} --", i + 1).unwrap();
+                }
+                writeln!(out, "{frame}").unwrap();
+            }
+        }
+
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Every, Frame};
+    use crate::{
+        common::example,
+        y2022::day_9::{parse_input, Motion, Point, Rope, Touching},
+    };
+
+    fn motions(input: &str) -> Result<Vec<Motion>> {
+        parse_input(input).collect()
+    }
+
+    fn puzzle_frame(min: (i32, i32), max: (i32, i32), knots: bool, trail: bool) -> Frame {
+        Frame {
+            min: Point { x: min.0, y: min.1 },
+            max: Point { x: max.0, y: max.1 },
+            knots,
+            trail,
+        }
+    }
+
+    #[test]
+    fn small_example() -> Result<()> {
+        let frame = puzzle_frame((0, 0), (5, 4), true, false);
+        let mut rope = Rope::new(2, Touching)?;
+        let frames = rope.animate(motions(example!(2022, "9-1"))?, Every::Motion, Some(frame))?;
+
+        // The state at the end of each motion, as in the puzzle.
+        let expected = [
+            ["......", "......", "......", "......", "s..TH."],
+            ["....H.", "....T.", "......", "......", "s....."],
+            [".HT...", "......", "......", "......", "s....."],
+            ["..T...", ".H....", "......", "......", "s....."],
+            ["......", "....TH", "......", "......", "s....."],
+            ["......", "....T.", ".....H", "......", "s....."],
+            ["......", "......", "HT....", "......", "s....."],
+            ["......", "......", ".TH...", "......", "s....."],
+        ];
+        assert_eq!(frames.len(), expected.len());
+        for (frame, rows) in frames.iter().zip(expected) {
+            assert_eq!(*frame, rows.join("\n") + "\n");
+        }
+
+        // Just the trail.
+        let trail = rope.render(&Frame {
+            knots: false,
+            trail: true,
+            ..frame
+        });
+        let expected = ["..##..", "...##.", ".####.", "....#.", "s###.."];
+        assert_eq!(trail, expected.join("\n") + "\n");
+
+        // Every step, the frame grows with the rope.
+        let mut rope = Rope::new(2, Touching)?;
+        let frames = rope.animate(motions("R 2\nU 1")?, Every::Step, None)?;
+        assert_eq!(frames, ["TH\n", "sTH\n", "..H\nsT.\n"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn large_example() -> Result<()> {
+        let mut rope = Rope::new(10, Touching)?;
+        let frame = puzzle_frame((-11, -5), (14, 15), true, false);
+        let frames = rope.animate(motions(example!(2022, "9-2"))?, Every::Motion, Some(frame))?;
+
+        // After R 5, the knots are strung out along the bottom.
+        let row = frames[0].lines().nth(15).unwrap();
+        assert_eq!(row, "...........54321H.........");
+
+        // After U 8, knots 1 to 4 have followed the head up, and the rest
+        // trail off diagonally.
+        let rows: Vec<_> = frames[1].lines().skip(7).take(9).collect();
+        let expected = [
+            "................H.........",
+            "................1.........",
+            "................2.........",
+            "................3.........",
+            "...............54.........",
+            "..............6...........",
+            ".............7............",
+            "............8.............",
+            "...........9..............",
+        ];
+        assert_eq!(rows, expected);
+
+        let trail = rope.render(&Frame {
+            knots: false,
+            trail: true,
+            ..frame
+        });
+        let expected = [
+            "..........................",
+            "..........................",
+            "..........................",
+            "..........................",
+            "..........................",
+            "..........................",
+            "..........................",
+            "..........................",
+            "..........................",
+            "#.........................",
+            "#.............###.........",
+            "#............#...#........",
+            ".#..........#.....#.......",
+            "..#..........#.....#......",
+            "...#........#.......#.....",
+            "....#......s.........#....",
+            ".....#..............#.....",
+            "......#............#......",
+            ".......#..........#.......",
+            "........#........#........",
+            ".........########.........",
+        ];
+        assert_eq!(trail, expected.join("\n") + "\n");
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace() -> Result<()> {
+        let mut rope = Rope::new(3, Touching)?;
+        let trace = rope.trace(motions("R 3\nTO 3,-1")?, Every::Motion)?;
+        let expected = ["== R 3 ==", "s21H", "", "== TO 3,-1 ==", "s21.", "...H", ""];
+        assert_eq!(trace, expected.join("\n") + "\n");
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+use anyhow::{ensure, Context, Result};
+
+use super::{Motion, Point, Trail};
+
+/// How a knot moves to keep up with the knot ahead of it.
+///
+/// Rules should only depend on where the knots are relative to each other, so
+/// that `Rope` can fast-forward through long motions.
+pub trait Follow {
+    /// Where `follower` ends up, after `leader` has moved.
+    fn follow(&self, leader: Point, follower: Point) -> Point;
+}
+
+/// The puzzle's rule: knots must stay touching, including diagonally. If they
+/// don't, the follower takes one step (diagonal if need be) towards the
+/// leader.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Touching;
+
+/// Knots can only touch, and move, orthogonally. The follower steps along
+/// whichever axis has the bigger gap (x, if they're equal) until it's touching
+/// again.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ManhattanOnly;
+
+/// Like `Touching`, but the knots can drift up to `slack` apart (in both x and
+/// y) before the follower has to catch up. A slack of 1 is `Touching`.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxSlack {
+    pub slack: u32,
+}
+
+impl Follow for Touching {
+    fn follow(&self, leader: Point, follower: Point) -> Point {
+        MaxSlack { slack: 1 }.follow(leader, follower)
+    }
+}
+
+impl Follow for ManhattanOnly {
+    fn follow(&self, leader: Point, mut follower: Point) -> Point {
+        loop {
+            let gap = leader - follower;
+            if gap.x.abs() + gap.y.abs() <= 1 {
+                return follower;
+            }
+
+            if gap.x.abs() >= gap.y.abs() {
+                follower.x += gap.x.signum();
+            } else {
+                follower.y += gap.y.signum();
+            }
+        }
+    }
+}
+
+impl Follow for MaxSlack {
+    fn follow(&self, leader: Point, mut follower: Point) -> Point {
+        loop {
+            let gap = leader - follower;
+            if gap.x.unsigned_abs() <= self.slack && gap.y.unsigned_abs() <= self.slack {
+                return follower;
+            }
+
+            follower.x += gap.x.signum();
+            follower.y += gap.y.signum();
+        }
+    }
+}
+
+/// A rope of knots, all starting at the origin. The head is `knots()[0]`, and
+/// the tail is the last knot.
+///
+/// We keep track of every position visited by one of the knots (the tail,
+/// unless you pick another with `tracking`).
+///
+/// Long motions are fast-forwarded, so `R 1000000000` is no slower than `R
+/// 10`. See `advance`. Each one leaves a run in the `Trail`, though, and
+/// counting the cells visited is quadratic in the number of runs.
+///
+/// The head can't go further than `LIMIT` from the origin along either axis.
+/// The other knots stay between places the head has been, so the gap between
+/// any two knots always fits in an `i32`.
+#[derive(Debug, Clone)]
+pub struct Rope<F> {
+    knots: Vec<Point>,
+    rule: F,
+    tracked: usize,
+    visited: Trail,
+}
+
+/// See `Rope`.
+pub const LIMIT: u32 = 1 << 30;
+
+impl<F: Follow> Rope<F> {
+    /// Fails if `n` is 0.
+    pub fn new(n: usize, rule: F) -> Result<Self> {
+        ensure!(n != 0, "a rope needs at least one knot");
+        Ok(Self {
+            knots: vec![Point::ORIGIN; n],
+            rule,
+            tracked: n - 1,
+            visited: Trail::from([Point::ORIGIN]),
+        })
+    }
+
+    /// Track knot `i` instead, starting over from its current position.
+    pub fn tracking(mut self, i: usize) -> Result<Self> {
+        let n = self.knots.len();
+        ensure!(i < n, "no knot {i}; there are {n}");
+        self.tracked = i;
+        self.visited = Trail::from([self.knots[i]]);
+        Ok(self)
+    }
+
+    pub fn knots(&self) -> &[Point] {
+        &self.knots
+    }
+
+    pub fn head(&self) -> Point {
+        self.knots[0]
+    }
+
+    pub fn tail(&self) -> Point {
+        *self.knots.last().unwrap()
+    }
+
+    /// The index of the tracked knot.
+    pub fn tracked(&self) -> usize {
+        self.tracked
+    }
+
+    /// Every position the tracked knot has been.
+    pub fn visited(&self) -> &Trail {
+        &self.visited
+    }
+
+    pub fn num_visited(&self) -> usize {
+        self.visited.len()
+    }
+
+    /// Move the head by `dir`, and then let each knot follow the one ahead of
+    /// it. Fails, without moving, if that would take the head past `LIMIT`.
+    pub fn step(&mut self, dir: Point) -> Result<()> {
+        reach(self.head(), dir, 1)?;
+        self.pull(dir);
+        Ok(())
+    }
+
+    /// Helper for `step` and `advance`, which check that `dir` is in range.
+    fn pull(&mut self, dir: Point) {
+        self.knots[0] = self.knots[0] + dir;
+        for i in 1..self.knots.len() {
+            self.knots[i] = self.rule.follow(self.knots[i - 1], self.knots[i]);
+        }
+        self.visited.insert(self.knots[self.tracked]);
+    }
+
+    /// Move the head through each of `m`'s steps. Fails, without moving, if
+    /// `m` would take the head past `LIMIT`.
+    pub fn apply(&mut self, m: Motion) -> Result<()> {
+        let mut head = self.head();
+        for (dir, n) in m.runs(head) {
+            head = reach(head, dir, n)?;
+        }
+        for (dir, n) in m.runs(self.head()) {
+            self.advance(dir, n)?;
+        }
+        Ok(())
+    }
+
+    /// Step the head `n` times in direction `dir`.
+    ///
+    /// Once a step moves every knot by exactly `dir`, the rope is in the same
+    /// shape as before, just shifted. So the next step will do the same thing,
+    /// and so will every one after it. At that point we can skip to the end,
+    /// and record the tracked knot's path as one straight run.
+    ///
+    /// Fails, without moving, if that would take the head past `LIMIT`.
+    pub fn advance(&mut self, dir: Point, n: u32) -> Result<()> {
+        reach(self.head(), dir, n)?;
+        let is_compass = dir != Point::ORIGIN && dir.x.abs() <= 1 && dir.y.abs() <= 1;
+        let mut prev = self.knots.clone();
+
+        for done in 1..=n {
+            self.pull(dir);
+
+            let settled = self.knots.iter().zip(&prev).all(|(&k, &p)| k == p + dir);
+            if is_compass && settled {
+                let left = n - done;
+                let start = self.knots[self.tracked] + dir;
+                self.visited.insert_run(start, dir, left);
+                for k in &mut self.knots {
+                    // In range, since the head is.
+                    *k = k.checked_shift(dir, left).unwrap();
+                }
+                return Ok(());
+            }
+            prev.copy_from_slice(&self.knots);
+        }
+        Ok(())
+    }
+
+    /// Apply each of `motions` in turn, stopping at the first that fails.
+    pub fn run(&mut self, motions: impl IntoIterator<Item = Motion>) -> Result<()> {
+        for m in motions {
+            self.apply(m)?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the head ends up after moving from `from` by `dir`, `n` times.
+/// Fails if that's past `LIMIT`.
+fn reach(from: Point, dir: Point, n: u32) -> Result<Point> {
+    let in_range = |p: &Point| p.x.unsigned_abs() <= LIMIT && p.y.unsigned_abs() <= LIMIT;
+    from.checked_shift(dir, n)
+        .filter(in_range)
+        .with_context(|| {
+            format!("moving {dir:?} {n} times from {from:?} goes more than {LIMIT} from the origin")
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{Follow, ManhattanOnly, MaxSlack, Rope, Touching, LIMIT};
+    use crate::{
+        common::{example, input, testing::XorShift},
+        y2022::day_9::{parse_input, parse_line, part_1, Motion, Point},
+    };
+
+    fn motions(input: &str) -> Result<Vec<Motion>> {
+        parse_input(input).collect()
+    }
+
+    fn count<F: Follow>(input: &str, n: usize, rule: F) -> Result<usize> {
+        let mut rope = Rope::new(n, rule)?;
+        rope.run(motions(input)?)?;
+        Ok(rope.num_visited())
+    }
+
+    #[test]
+    fn example() -> Result<()> {
+        let small = example!(2022, "9-1");
+        let large = example!(2022, "9-2");
+
+        assert_eq!(count(small, 2, Touching)?, 13);
+        assert_eq!(count(small, 10, Touching)?, 1);
+        assert_eq!(count(large, 10, Touching)?, 36);
+
+        // A slack of 1 is the same as touching.
+        assert_eq!(count(large, 10, MaxSlack { slack: 1 })?, 36);
+        Ok(())
+    }
+
+    #[test]
+    fn rules() {
+        let p = |x, y| Point { x, y };
+        let leader = p(2, 1);
+
+        assert_eq!(Touching.follow(leader, p(0, 0)), p(1, 1));
+        assert_eq!(Touching.follow(leader, p(1, 0)), p(1, 0));
+        assert_eq!(ManhattanOnly.follow(leader, p(0, 0)), p(2, 0));
+        assert_eq!(ManhattanOnly.follow(leader, p(1, 0)), p(2, 0));
+        assert_eq!(ManhattanOnly.follow(p(0, 2), p(0, 0)), p(0, 1));
+        assert_eq!(MaxSlack { slack: 2 }.follow(leader, p(0, 0)), p(0, 0));
+        assert_eq!(MaxSlack { slack: 2 }.follow(p(3, 0), p(0, 0)), p(1, 0));
+        assert_eq!(MaxSlack { slack: 0 }.follow(leader, p(0, 0)), leader);
+        let slack = u32::MAX;
+        assert_eq!(MaxSlack { slack }.follow(leader, p(0, 0)), p(0, 0));
+    }
+
+    #[test]
+    fn tracking() -> Result<()> {
+        let large = example!(2022, "9-2");
+
+        // A single knot is its own tail, so it visits every cell the head does.
+        let mut head = Rope::new(1, Touching)?;
+        head.run(motions(large)?)?;
+        let mut tracked = Rope::new(10, Touching)?.tracking(0)?;
+        tracked.run(motions(large)?)?;
+        assert_eq!(tracked.visited().to_set(), head.visited().to_set());
+
+        // Knot 1 of a long rope moves just like the tail of a short one.
+        let mut short = Rope::new(2, Touching)?;
+        short.run(motions(large)?)?;
+        let mut knot_1 = Rope::new(10, Touching)?.tracking(1)?;
+        knot_1.run(motions(large)?)?;
+        assert_eq!(knot_1.visited().to_set(), short.visited().to_set());
+
+        assert!(Rope::new(0, Touching).is_err());
+        assert!(Rope::new(2, Touching)?.tracking(2).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn other_rules() -> Result<()> {
+        let small = example!(2022, "9-1");
+
+        // More slack means less following.
+        let counts: Vec<_> = (0..4)
+            .map(|slack| count(small, 2, MaxSlack { slack }))
+            .collect::<Result<_>>()?;
+        assert!(counts.windows(2).all(|w| w[0] >= w[1]));
+
+        // With no slack the tail visits every cell the head does.
+        assert_eq!(counts[0], count(small, 1, Touching)?);
+
+        // With endless slack it never moves.
+        assert_eq!(count(small, 2, MaxSlack { slack: u32::MAX })?, 1);
+
+        // Orthogonal moves stay orthogonally touching.
+        let mut rope = Rope::new(5, ManhattanOnly)?;
+        for m in motions(small)? {
+            rope.apply(m)?;
+            for w in rope.knots().windows(2) {
+                let gap = w[0] - w[1];
+                assert!(gap.x.abs() + gap.y.abs() <= 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// Without fast-forwarding.
+    fn step_by_step<F: Follow>(rope: &mut Rope<F>, motions: &[Motion]) {
+        for m in motions {
+            for dir in m.steps(rope.head()) {
+                rope.step(dir).unwrap();
+            }
+        }
+    }
+
+    fn compare<F: Follow + Clone>(rope: Rope<F>, motions: &[Motion]) {
+        let mut fast = rope.clone();
+        fast.run(motions.iter().copied()).unwrap();
+        let mut slow = rope;
+        step_by_step(&mut slow, motions);
+
+        assert_eq!(fast.knots(), slow.knots());
+        assert_eq!(fast.num_visited(), slow.num_visited());
+        assert_eq!(fast.visited().to_set(), slow.visited().to_set());
+    }
+
+    #[test]
+    fn fast_forward_matches_stepping() -> Result<()> {
+        compare(Rope::new(2, Touching)?, &motions(input!(2022, 9))?);
+        compare(Rope::new(10, Touching)?, &motions(input!(2022, 9))?);
+
+        let mut rng = XorShift::new(9);
+        let codes = ["L", "R", "U", "D", "UL", "UR", "DL", "DR"];
+        for _ in 0..200 {
+            let lines: Vec<_> = (0..1 + rng.below(15))
+                .map(|_| match rng.below(6) {
+                    0 => {
+                        let x = rng.below(61) as i32 - 30;
+                        let y = rng.below(61) as i32 - 30;
+                        format!("TO {x},{y}")
+                    }
+                    _ => {
+                        let code = codes[rng.below(8) as usize];
+                        format!("{code} {}", rng.below(40))
+                    }
+                })
+                .collect();
+            let motions = motions(&lines.join("\n"))?;
+
+            let n = 1 + rng.below(12) as usize;
+            let tracked = rng.below(n as u64) as usize;
+            match rng.below(3) {
+                0 => compare(Rope::new(n, Touching)?.tracking(tracked)?, &motions),
+                1 => compare(Rope::new(n, ManhattanOnly)?.tracking(tracked)?, &motions),
+                _ => {
+                    let slack = rng.below(4) as u32;
+                    compare(
+                        Rope::new(n, MaxSlack { slack })?.tracking(tracked)?,
+                        &motions,
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn huge_motions() -> Result<()> {
+        let billion = 1_000_000_000;
+
+        // The tail trails 9 behind the head.
+        let huge = format!("R {billion}");
+        assert_eq!(count(&huge, 10, Touching)?, billion - 8);
+
+        // A right triangle, where the diagonal comes back to the start.
+        let huge = format!("R {billion}\nU {billion}\nDL {billion}");
+        assert_eq!(count(&huge, 1, Touching)?, 3 * billion);
+
+        let mut rope = Rope::new(10, Touching)?;
+        rope.run(motions(&format!("UR {billion}\nTO 0,0\nL 5"))?)?;
+        assert_eq!(rope.head().x, -5);
+        assert_eq!(rope.num_visited(), billion - 9 + 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn out_of_range() -> Result<()> {
+        let billion = 1_000_000_000;
+        let too_far = format!("R {}\nR {}", 2 * billion, 2 * billion);
+        assert!(part_1(&too_far).is_err());
+
+        // A motion that would go too far doesn't move the rope at all, not
+        // even part of the way.
+        let mut rope = Rope::new(3, Touching)?;
+        rope.run(motions(&format!("L {billion}\nD {billion}"))?)?;
+        let knots = rope.knots().to_vec();
+        for m in ["L 100000000", "DL 100000000", "TO 0,-2000000000"] {
+            assert!(rope.apply(parse_line(m)?).is_err(), "{m}");
+            assert_eq!(rope.knots(), knots);
+        }
+
+        // Right up to the limit is fine, from one side to the other.
+        let limit = LIMIT as i32;
+        let mut rope = Rope::new(2, Touching)?;
+        rope.run(motions(&format!("TO {limit},0\nTO -{limit},0"))?)?;
+        assert_eq!(rope.num_visited(), 2 * LIMIT as usize - 1);
+        assert!(rope.step(Point { x: -1, y: 0 }).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,295 @@
+use std::collections::{HashMap, HashSet};
+
+use super::Point;
+
+/// A set of cells, stored as single cells plus long straight runs.
+///
+/// Runs go along one of the eight compass directions, so each lies on a
+/// horizontal, vertical or diagonal line. Runs on the same line are merged,
+/// so counting only has to worry about the (few) places where lines of
+/// different directions cross.
+#[derive(Debug, Clone, Default)]
+pub struct Trail {
+    cells: HashSet<Point>,
+    /// Keyed by the line: its direction (one of `LINE_DIRS`), and `cross(dir,
+    /// p)` for any point `p` on it. The values are sorted, disjoint, and
+    /// non-adjacent ranges of `param(dir, p)`.
+    runs: HashMap<(Point, i64), Vec<(i64, i64)>>,
+}
+
+/// One direction per kind of line; the other four compass directions are
+/// their opposites.
+const LINE_DIRS: [Point; 4] = [
+    Point { x: 1, y: 0 },
+    Point { x: 0, y: 1 },
+    Point { x: 1, y: 1 },
+    Point { x: 1, y: -1 },
+];
+
+/// Constant along any line in direction `dir`.
+fn cross(dir: Point, p: Point) -> i64 {
+    dir.x as i64 * p.y as i64 - dir.y as i64 * p.x as i64
+}
+
+/// Increases by 1 per step along a line in direction `dir`.
+fn param(dir: Point, p: Point) -> i64 {
+    if dir.x != 0 {
+        p.x as i64
+    } else {
+        p.y as i64
+    }
+}
+
+/// The point on line (`dir`, `key`) with the given `param`.
+fn at(dir: Point, key: i64, param: i64) -> Point {
+    // cross(dir, p) = dir.x * p.y - dir.y * p.x
+    let (x, y) = if dir.x != 0 {
+        (param, (key + dir.y as i64 * param) * dir.x as i64)
+    } else {
+        (-key * dir.y as i64, param)
+    };
+    Point {
+        x: x as i32,
+        y: y as i32,
+    }
+}
+
+/// Where two lines of different directions cross, if that's a lattice point.
+fn intersect((d1, k1): (Point, i64), (d2, k2): (Point, i64)) -> Option<Point> {
+    // Solve cross(d1, p) = k1 and cross(d2, p) = k2 for p, by Cramer's rule.
+    let (a, b) = (-d1.y as i64, d1.x as i64);
+    let (c, e) = (-d2.y as i64, d2.x as i64);
+    let det = a * e - b * c;
+    let x = k1 * e - b * k2;
+    let y = a * k2 - c * k1;
+
+    if det == 0 || x % det != 0 || y % det != 0 {
+        return None;
+    }
+    Some(Point {
+        x: (x / det) as i32,
+        y: (y / det) as i32,
+    })
+}
+
+impl<const N: usize> From<[Point; N]> for Trail {
+    fn from(cells: [Point; N]) -> Self {
+        Self {
+            cells: HashSet::from(cells),
+            runs: HashMap::new(),
+        }
+    }
+}
+
+impl Trail {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, p: Point) {
+        self.cells.insert(p);
+    }
+
+    /// Insert the `n` cells `start`, `start + dir`, and so on. `dir` must be
+    /// one of the eight compass directions.
+    pub fn insert_run(&mut self, start: Point, dir: Point, n: u32) {
+        assert!(
+            dir != Point::ORIGIN && dir.x.abs() <= 1 && dir.y.abs() <= 1,
+            "runs have to go in a compass direction, not {dir:?}"
+        );
+        if n == 0 {
+            return;
+        }
+
+        let line_dir = if LINE_DIRS.contains(&dir) {
+            dir
+        } else {
+            Point::ORIGIN - dir
+        };
+        let key = cross(line_dir, start);
+        let a = param(line_dir, start);
+        let b = a + (n as i64 - 1) * param(line_dir, dir);
+        let (mut lo, mut hi) = (a.min(b), a.max(b));
+
+        // Merge with anything overlapping or adjacent.
+        let ranges = self.runs.entry((line_dir, key)).or_default();
+        let first = ranges.partition_point(|&(_, h)| h + 1 < lo);
+        let last = ranges.partition_point(|&(l, _)| l <= hi + 1);
+        if first < last {
+            lo = lo.min(ranges[first].0);
+            hi = hi.max(ranges[last - 1].1);
+        }
+        ranges.splice(first..last, [(lo, hi)]);
+    }
+
+    /// Whether `p` is on any of the runs.
+    fn on_run(&self, p: Point) -> bool {
+        LINE_DIRS.iter().any(|&dir| {
+            let Some(ranges) = self.runs.get(&(dir, cross(dir, p))) else {
+                return false;
+            };
+            let t = param(dir, p);
+            let i = ranges.partition_point(|&(_, hi)| hi < t);
+            ranges.get(i).is_some_and(|&(lo, _)| lo <= t)
+        })
+    }
+
+    pub fn contains(&self, p: Point) -> bool {
+        self.cells.contains(&p) || self.on_run(p)
+    }
+
+    /// The number of distinct cells.
+    ///
+    /// Add up the lengths of the runs, and then take off the double counting
+    /// where lines cross: a cell on `k` lines was counted `k` times. This is
+    /// quadratic in the number of runs, but not in their lengths.
+    pub fn len(&self) -> usize {
+        let ranges: Vec<_> = self
+            .runs
+            .iter()
+            .flat_map(|(&line, ranges)| ranges.iter().map(move |&r| (line, r)))
+            .collect();
+
+        let mut total: i64 = ranges.iter().map(|(_, (lo, hi))| hi - lo + 1).sum();
+
+        let mut crossings: HashMap<Point, u8> = HashMap::new();
+        for (i, &(line_1, (lo_1, hi_1))) in ranges.iter().enumerate() {
+            for &(line_2, (lo_2, hi_2)) in &ranges[i + 1..] {
+                let Some(p) = intersect(line_1, line_2) else {
+                    continue;
+                };
+                let t_1 = param(line_1.0, p);
+                let t_2 = param(line_2.0, p);
+                if (lo_1..=hi_1).contains(&t_1) && (lo_2..=hi_2).contains(&t_2) {
+                    let bit = |dir| 1 << LINE_DIRS.iter().position(|&d| d == dir).unwrap();
+                    *crossings.entry(p).or_default() |= bit(line_1.0) | bit(line_2.0);
+                }
+            }
+        }
+        for lines in crossings.into_values() {
+            total -= lines.count_ones() as i64 - 1;
+        }
+
+        let loose = self.cells.iter().filter(|&&p| !self.on_run(p)).count();
+        total as usize + loose
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty() && self.runs.is_empty()
+    }
+
+    /// The bottom-left and top-right corners of the bounding box, if there's
+    /// anything in it.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let ends = self.runs.iter().flat_map(|(&(dir, key), ranges)| {
+            ranges
+                .iter()
+                .flat_map(move |&(lo, hi)| [at(dir, key, lo), at(dir, key, hi)])
+        });
+
+        let mut points = self.cells.iter().copied().chain(ends);
+        let first = points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (
+                Point {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Point {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        }))
+    }
+
+    /// Every cell, spelled out. Don't call this after a motion of a billion
+    /// steps.
+    pub fn to_set(&self) -> HashSet<Point> {
+        let mut set = self.cells.clone();
+        for (&(dir, key), ranges) in &self.runs {
+            for &(lo, hi) in ranges {
+                set.extend((lo..=hi).map(|t| at(dir, key, t)));
+            }
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::{at, cross, param, Trail, LINE_DIRS};
+    use crate::{common::testing::XorShift, y2022::day_9::Point};
+
+    #[test]
+    fn lines() {
+        let p = Point { x: 3, y: -7 };
+        for dir in LINE_DIRS {
+            assert_eq!(at(dir, cross(dir, p), param(dir, p)), p);
+        }
+    }
+
+    #[test]
+    fn matches_a_set() {
+        let mut rng = XorShift::new(47);
+        let compass: Vec<_> = (-1..=1)
+            .flat_map(|x| (-1..=1).map(move |y| Point { x, y }))
+            .filter(|&d| d != Point::ORIGIN)
+            .collect();
+
+        let random_point = |rng: &mut XorShift| Point {
+            x: rng.below(21) as i32 - 10,
+            y: rng.below(21) as i32 - 10,
+        };
+
+        for _ in 0..200 {
+            let mut trail = Trail::new();
+            let mut set = HashSet::new();
+
+            for _ in 0..rng.below(12) {
+                if rng.below(3) == 0 {
+                    let p = random_point(&mut rng);
+                    trail.insert(p);
+                    set.insert(p);
+                } else {
+                    let start = random_point(&mut rng);
+                    let dir = compass[rng.below(8) as usize];
+                    let n = rng.below(12) as u32;
+                    trail.insert_run(start, dir, n);
+                    for k in 0..n as i32 {
+                        set.insert(Point {
+                            x: start.x + k * dir.x,
+                            y: start.y + k * dir.y,
+                        });
+                    }
+                }
+            }
+
+            assert_eq!(trail.len(), set.len());
+            assert_eq!(trail.to_set(), set);
+            for x in -22..22 {
+                for y in -22..22 {
+                    let p = Point { x, y };
+                    assert_eq!(trail.contains(p), set.contains(&p));
+                }
+            }
+
+            let bounds = set.iter().fold(None, |b: Option<(Point, Point)>, &p| {
+                let (min, max) = b.unwrap_or((p, p));
+                Some((
+                    Point {
+                        x: min.x.min(p.x),
+                        y: min.y.min(p.y),
+                    },
+                    Point {
+                        x: max.x.max(p.x),
+                        y: max.y.max(p.y),
+                    },
+                ))
+            });
+            assert_eq!(trail.bounds(), bounds);
+        }
+    }
+}